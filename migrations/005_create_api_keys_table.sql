@@ -0,0 +1,15 @@
+-- API anahtarları tablosunu oluştur (anahtarların kendisi değil, SHA-256 hash'leri saklanır)
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR NOT NULL,
+    prefix VARCHAR(16) NOT NULL, -- Anahtarın görüntülenebilir ön eki (örn: rmk_1a2b3c4d)
+    key_hash VARCHAR(64) UNIQUE NOT NULL, -- Anahtarın SHA-256 hash'i (hex)
+    scopes TEXT[] NOT NULL DEFAULT '{}', -- Yetki kapsamları (örn: ports:read)
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- İndeksler oluştur
+CREATE INDEX IF NOT EXISTS idx_api_keys_created_at ON api_keys(created_at);
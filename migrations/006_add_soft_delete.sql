@@ -0,0 +1,14 @@
+-- Kullanıcılar ve limanlar için soft delete (silinen kayıtlar saklama süresi dolana kadar geri yüklenebilir)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE ports ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Benzersizlik yalnızca silinmemiş kayıtlar için geçerli olsun; silinen bir kaydın email'i/kodu yeniden kullanılabilir.
+-- İndeks adları eski kısıt adlarıyla aynı tutulur, böylece hata kodları (email_taken, port_code_taken) değişmez.
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key;
+ALTER TABLE ports DROP CONSTRAINT IF EXISTS ports_code_key;
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_key ON users(email) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS ports_code_key ON ports(code) WHERE deleted_at IS NULL;
+
+-- Temizleme işi için indeksler
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_ports_deleted_at ON ports(deleted_at) WHERE deleted_at IS NOT NULL;
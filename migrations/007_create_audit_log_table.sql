@@ -0,0 +1,28 @@
+-- Kullanıcı ve liman değişikliklerinin denetim kaydı (yalnızca ekleme yapılabilir)
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    actor VARCHAR NOT NULL, -- Değişikliği yapan kimlik (örn: api_key:<uuid>, oidc:<sub>, bootstrap, system:purge)
+    action VARCHAR NOT NULL CHECK (action IN ('create', 'update', 'delete', 'restore', 'purge')),
+    entity_type VARCHAR NOT NULL CHECK (entity_type IN ('user', 'port')),
+    entity_id UUID NOT NULL,
+    before JSONB, -- Değişiklikten önceki kayıt (create için boş)
+    after JSONB -- Değişiklikten sonraki kayıt (purge için boş)
+);
+
+-- İndeksler oluştur
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity_type, entity_id, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(actor, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+
+-- Kayıtların sonradan değiştirilmesini veya silinmesini engelle
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log yalnızca ekleme yapılabilir bir tablodur';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_no_update ON audit_log;
+CREATE TRIGGER audit_log_no_update
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,18 @@
+-- GDPR silme talebi: kişisel veriler silinir, kayıt (kaba konumu ile) heatmap'ler için kalır
+ALTER TABLE users ADD COLUMN IF NOT EXISTS erased_at TIMESTAMPTZ;
+
+-- Silme talebi denetim kaydına 'erase' işlemi olarak yazılır
+ALTER TABLE audit_log DROP CONSTRAINT IF EXISTS audit_log_action_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_action_check
+    CHECK (action IN ('create', 'update', 'delete', 'restore', 'purge', 'erase'));
+
+-- Denetim kaydı yalnızca ekleme yapılabilir kalır; tek istisna, silme talebi sırasında aynı transaction'da
+-- `SET LOCAL rustmicro.audit_scrub = 'on'` ile kişisel verilerin güncellenmesidir (DELETE her zaman engellenir)
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND current_setting('rustmicro.audit_scrub', true) = 'on' THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'audit_log yalnızca ekleme yapılabilir bir tablodur';
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+-- Kullanıcının konum hassasiyeti onayı; koordinatlar kayıt sırasında bu hassasiyete indirgenir
+ALTER TABLE users ADD COLUMN IF NOT EXISTS location_precision TEXT NOT NULL DEFAULT 'exact'
+    CHECK (location_precision IN ('exact', 'neighbourhood', 'city', 'none'));
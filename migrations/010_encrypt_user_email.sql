@@ -0,0 +1,18 @@
+-- Email alanı uygulamada AES-256-GCM ile şifrelenir (nonce || ciphertext); email_hash eşitlik araması ve
+-- benzersizlik için HMAC-SHA256 kör indeksidir. Düz metin email'ler açılışta şifrelenip NULL yapılır.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_ciphertext BYTEA;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_key_id TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_hash TEXT;
+ALTER TABLE users ALTER COLUMN email DROP NOT NULL;
+
+ALTER TABLE users ADD CONSTRAINT users_email_present CHECK (
+    email IS NOT NULL
+    OR (email_ciphertext IS NOT NULL AND email_key_id IS NOT NULL AND email_hash IS NOT NULL)
+);
+
+-- Benzersizlik kör indekse taşınır; hata eşlemesinin çalışmaya devam etmesi için isim korunur
+DROP INDEX IF EXISTS users_email_key;
+CREATE UNIQUE INDEX users_email_key ON users (email_hash) WHERE deleted_at IS NULL;
+
+DROP INDEX IF EXISTS idx_users_email;
+CREATE INDEX IF NOT EXISTS idx_users_email_key_id ON users (email_key_id);
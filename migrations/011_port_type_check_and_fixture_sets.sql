@@ -0,0 +1,11 @@
+-- Liman tipi kısıtı (004'teki tanım hiç uygulanmamıştı); feribot limanları için 'ferry' eklendi
+ALTER TABLE ports DROP CONSTRAINT IF EXISTS ports_port_type_check;
+ALTER TABLE ports ADD CONSTRAINT ports_port_type_check
+    CHECK (port_type IN ('container', 'cruise', 'cargo', 'fishing', 'ferry'));
+
+-- Yüklenmiş örnek veri setleri ve sürümleri (rustmicro seed)
+CREATE TABLE IF NOT EXISTS fixture_sets (
+    name TEXT PRIMARY KEY,
+    version INTEGER NOT NULL,
+    loaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
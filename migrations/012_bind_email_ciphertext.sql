@@ -0,0 +1,5 @@
+-- Email şifrelemesinde kör indeks (email_hash) AES-GCM ek doğrulanmış verisi (AAD) olarak kullanılır; böylece
+-- şifreli email başka bir kullanıcının satırına taşınırsa çözülemez. Bu sütundan önce AAD'siz şifrelenmiş
+-- satırlar FALSE kalır ve açılışta etkin anahtarla yeniden şifrelenir; yeni satırlar her zaman AAD ile yazılır.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_aad_bound BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ALTER COLUMN email_aad_bound SET DEFAULT TRUE;
@@ -0,0 +1,368 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{forward_ready, ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage, ResponseError};
+use chrono::Utc;
+use futures_util::future::LocalBoxFuture;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::oidc::OidcValidator;
+use crate::rate_limit;
+
+/// API anahtarlarının ön eki; Bearer token'ın API anahtarı olduğunu buradan anlarız
+pub const API_KEY_PREFIX: &str = "rmk_";
+
+/// Route bazında istenebilecek yetki kapsamları
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum Scope {
+    #[serde(rename = "ports:read")]
+    PortsRead,
+    #[serde(rename = "ports:write")]
+    PortsWrite,
+    #[serde(rename = "users:read")]
+    UsersRead,
+    #[serde(rename = "users:write")]
+    UsersWrite,
+    #[serde(rename = "heatmap:read")]
+    HeatmapRead,
+    #[serde(rename = "stats:read")]
+    StatsRead,
+    /// Tüm kapsamları içerir (API anahtarı yönetimi dahil)
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 7] = [
+        Scope::PortsRead,
+        Scope::PortsWrite,
+        Scope::UsersRead,
+        Scope::UsersWrite,
+        Scope::HeatmapRead,
+        Scope::StatsRead,
+        Scope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::PortsRead => "ports:read",
+            Scope::PortsWrite => "ports:write",
+            Scope::UsersRead => "users:read",
+            Scope::UsersWrite => "users:write",
+            Scope::HeatmapRead => "heatmap:read",
+            Scope::StatsRead => "stats:read",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("Bilinmeyen kapsam: {}", s))
+    }
+}
+
+/// İsteği yapan kimliğin türü
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrincipalKind {
+    /// Veritabanında kayıtlı bir API anahtarı
+    ApiKey(Uuid),
+    /// Şirket SSO'sundan gelen OIDC token'ı (`sub` claim'i)
+    Oidc(String),
+    /// Konfigürasyondaki ADMIN_API_KEY ile gelen istek
+    Bootstrap,
+}
+
+/// Kimliği doğrulanmış istek sahibi; request extension'larına eklenir
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub kind: PrincipalKind,
+    pub name: String,
+    pub scopes: HashSet<Scope>,
+}
+
+impl Principal {
+    /// Admin kapsamı diğer tüm kapsamları kapsar
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::Admin) || self.scopes.contains(&scope)
+    }
+
+    /// Denetim kayıtlarında kullanılan, isimden bağımsız sabit kimlik
+    pub fn actor_id(&self) -> String {
+        match &self.kind {
+            PrincipalKind::ApiKey(id) => format!("api_key:{}", id),
+            PrincipalKind::Oidc(subject) => format!("oidc:{}", subject),
+            PrincipalKind::Bootstrap => "bootstrap".to_string(),
+        }
+    }
+}
+
+/// Kota ve bütçe hesaplarında çağıranı tanımlayan anahtar: kimliği doğrulanmışsa aktör kimliği, değilse IP adresi
+///
+/// `X-Forwarded-For`/`Forwarded` başlıkları istemci tarafından serbestçe yazılabildiği için yalnızca
+/// `trust_proxy_headers` açıksa (uygulama bu başlıkları ezen bir proxy'nin arkasındaysa) kullanılır.
+pub fn caller_key(principal: Option<&Principal>, connection: &ConnectionInfo, trust_proxy_headers: bool) -> String {
+    if let Some(principal) = principal {
+        return principal.actor_id();
+    }
+    let ip = if trust_proxy_headers {
+        connection.realip_remote_addr()
+    } else {
+        connection.peer_addr()
+    };
+    format!("ip:{}", ip.unwrap_or("unknown"))
+}
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PrincipalKind::Bootstrap => write!(f, "{}", self.actor_id()),
+            _ => write!(f, "{} ({})", self.actor_id(), self.name),
+        }
+    }
+}
+
+/// Yeni bir API anahtarı üretir: (tam anahtar, görüntülenebilir ön ek)
+pub fn generate_api_key() -> (String, String) {
+    let mut bytes = [0u8; 24];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let secret = hex::encode(bytes);
+    let prefix = format!("{}{}", API_KEY_PREFIX, &secret[..8]);
+    (format!("{}_{}", prefix, &secret[8..]), prefix)
+}
+
+/// API anahtarının veritabanında saklanan SHA-256 hash'ini hesaplar
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// İstekten kimlik bilgisini çıkarır (`Authorization: Bearer` veya `X-API-Key`)
+fn extract_credential(req: &ServiceRequest) -> Option<String> {
+    if let Some(value) = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            return Some(token.trim().to_string());
+        }
+    }
+
+    req.headers()
+        .get("X-API-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+}
+
+fn unauthorized(message: &str) -> AppError {
+    AppError::Unauthorized(message.to_string())
+}
+
+async fn resolve_principal(req: &ServiceRequest, credential: &str) -> Result<Principal, AppError> {
+    let key_hash = hash_api_key(credential);
+
+    // Bootstrap admin anahtarı (ilk API anahtarlarını oluşturabilmek için)
+    if let Some(config) = req.app_data::<web::Data<AppConfig>>() {
+        if let Some(admin_key) = &config.auth.admin_api_key {
+            if hash_api_key(admin_key) == key_hash {
+                return Ok(Principal {
+                    kind: PrincipalKind::Bootstrap,
+                    name: "bootstrap".to_string(),
+                    scopes: HashSet::from([Scope::Admin]),
+                });
+            }
+        }
+    }
+
+    if !credential.starts_with(API_KEY_PREFIX) {
+        // API anahtarı değilse OIDC bearer token olarak doğrula
+        let Some(oidc) = req.app_data::<web::Data<OidcValidator>>() else {
+            return Err(unauthorized("Geçersiz kimlik bilgisi"));
+        };
+        return oidc.validate(credential).await.map_err(|e| {
+            tracing::warn!(error = %e, "OIDC doğrulama hatası");
+            unauthorized("Geçersiz veya süresi dolmuş token")
+        });
+    }
+
+    let Some(db) = req.app_data::<web::Data<Database>>() else {
+        return Err(AppError::Internal("Database uygulama verisine eklenmemiş".to_string()));
+    };
+
+    let api_key = match db.get_api_key_by_hash(&key_hash).await {
+        Ok(Some(api_key)) => api_key,
+        Ok(None) => return Err(unauthorized("Geçersiz API anahtarı")),
+        Err(e) => return Err(e.into()),
+    };
+
+    if api_key.revoked_at.is_some() {
+        return Err(unauthorized("API anahtarı iptal edilmiş"));
+    }
+    if api_key.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(unauthorized("API anahtarının süresi dolmuş"));
+    }
+
+    if let Err(e) = db.touch_api_key(api_key.id).await {
+        tracing::warn!(error = %e, api_key_id = %api_key.id, "API anahtarının son kullanım zamanı güncellenemedi");
+    }
+
+    Ok(Principal {
+        kind: PrincipalKind::ApiKey(api_key.id),
+        name: api_key.name,
+        scopes: api_key.scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+    })
+}
+
+/// Kimlik doğrulama middleware'i
+///
+/// Kimlik bilgisi yoksa istek anonim olarak devam eder; geçersiz, süresi dolmuş veya
+/// iptal edilmiş bir anahtar ya da doğrulanamayan bir OIDC token'ı ise 401 döner ve deneme IP adresine yazılır;
+/// başarısız deneme sınırını aşan IP'lerin kimlik bilgileri denetlenmeden 429 döner. Geçerli kimlikler `Principal` olarak
+/// request extension'larına eklenir ve kapsam kontrolü `RequireScope` ile yapılır.
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(credential) = extract_credential(&req) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    if let Some(response) = rate_limit::reject_auth_attempt(&req) {
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    match resolve_principal(&req, &credential).await {
+        Ok(principal) => {
+            req.extensions_mut().insert(principal);
+            Ok(next.call(req).await?.map_into_left_body())
+        }
+        Err(error) => {
+            if error.status_code() == StatusCode::UNAUTHORIZED {
+                rate_limit::record_auth_failure(&req);
+            }
+            Ok(req.into_response(error.error_response()).map_into_right_body())
+        }
+    }
+}
+
+/// Route'a erişim için belirli bir kapsam isteyen middleware
+///
+/// ```ignore
+/// .route("", web::get().to(get_all_users).wrap(RequireScope::new(Scope::UsersRead)))
+/// ```
+pub struct RequireScope {
+    scope: Scope,
+}
+
+impl RequireScope {
+    pub fn new(scope: Scope) -> Self {
+        Self { scope }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireScopeMiddleware {
+            service: Rc::new(service),
+            scope: self.scope,
+        }))
+    }
+}
+
+pub struct RequireScopeMiddleware<S> {
+    service: Rc<S>,
+    scope: Scope,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let allowed = req
+            .extensions()
+            .get::<Principal>()
+            .map(|principal| principal.has_scope(self.scope));
+
+        let response = match allowed {
+            Some(true) => {
+                let service = Rc::clone(&self.service);
+                return Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) });
+            }
+            Some(false) => AppError::Forbidden(format!("Bu işlem için '{}' kapsamı gerekli", self.scope)),
+            None => unauthorized("Kimlik doğrulaması gerekli"),
+        }
+        .error_response();
+
+        Box::pin(async move { Ok(req.into_response(response).map_into_right_body()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trip() {
+        for scope in Scope::ALL {
+            assert_eq!(scope.as_str().parse::<Scope>().unwrap(), scope);
+        }
+        assert!("ports:delete".parse::<Scope>().is_err());
+    }
+
+    #[test]
+    fn test_generate_api_key() {
+        let (key, prefix) = generate_api_key();
+        assert!(key.starts_with(&prefix));
+        assert!(prefix.starts_with(API_KEY_PREFIX));
+        assert_eq!(hash_api_key(&key).len(), 64);
+        assert_ne!(generate_api_key().0, key);
+    }
+
+    #[test]
+    fn test_admin_scope_implies_all() {
+        let principal = Principal {
+            kind: PrincipalKind::Bootstrap,
+            name: "test".to_string(),
+            scopes: HashSet::from([Scope::Admin]),
+        };
+        assert!(Scope::ALL.iter().all(|scope| principal.has_scope(*scope)));
+    }
+}
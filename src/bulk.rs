@@ -0,0 +1,486 @@
+use std::collections::HashSet;
+
+use actix_web::web::Bytes;
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::models::{BulkFormat, CreateUser, ImportAborted, ImportReport, ImportRowError, User, UserResponse};
+use crate::validation::{Validate, ValidationErrors};
+
+/// Veritabanına tek seferde yazılan satır sayısı
+pub const IMPORT_BATCH_SIZE: usize = 1000;
+/// Dışa aktarmada veritabanından tek seferde okunan satır sayısı
+pub const EXPORT_PAGE_SIZE: i64 = 1000;
+/// Raporda ayrıntısı verilen en fazla hatalı satır
+pub const MAX_REPORTED_ROW_ERRORS: usize = 1000;
+/// Bir satırın satır sonu hariç en fazla bayt uzunluğu
+pub const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// `MAX_LINE_BYTES` sınırını aşan satır; içeriği bellekte tutulmadan atlanır
+#[derive(Debug, PartialEq, Eq)]
+pub struct LineTooLong;
+
+/// Akış halinde gelen gövde parçalarını satırlara böler
+///
+/// Tamponda satır sonu olmadan `MAX_LINE_BYTES`'tan fazla veri biriktiğinde satırın geri kalanı bir sonraki
+/// satır sonuna kadar atılır; böylece satır sonu içermeyen bir gövde belleği doldurmaz.
+#[derive(Default)]
+pub struct LineSplitter {
+    buffer: Vec<u8>,
+    overflowed: bool,
+}
+
+impl LineSplitter {
+    /// Parçayı ekler ve tamamlanan satırları döndürür (satır sonu karakterleri hariç)
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Result<Vec<u8>, LineTooLong>> {
+        let mut lines = Vec::new();
+        for part in chunk.split_inclusive(|b| *b == b'\n') {
+            let (content, complete) = match part.split_last() {
+                Some((b'\n', content)) => (content, true),
+                _ => (part, false),
+            };
+            if !self.overflowed {
+                self.buffer.extend_from_slice(content);
+                // Satır sonu karakteri sınıra sayılmaz
+                let limit = MAX_LINE_BYTES + usize::from(self.buffer.last() == Some(&b'\r'));
+                if self.buffer.len() > limit {
+                    self.overflowed = true;
+                    self.buffer = Vec::new();
+                }
+            }
+            if complete {
+                lines.push(self.take_line());
+            }
+        }
+        lines
+    }
+
+    /// Sonunda satır sonu olmayan son satırı döndürür
+    pub fn finish(mut self) -> Option<Result<Vec<u8>, LineTooLong>> {
+        (self.overflowed || !self.buffer.is_empty()).then(|| self.take_line())
+    }
+
+    fn take_line(&mut self) -> Result<Vec<u8>, LineTooLong> {
+        if std::mem::take(&mut self.overflowed) {
+            return Err(LineTooLong);
+        }
+        let mut line = std::mem::take(&mut self.buffer);
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(line)
+    }
+}
+
+/// Tırnaklı bir alan satır sonunda kapanmıyorsa true (alan birden fazla satıra yayılıyor)
+///
+/// Gövde satır satır işlendiği için tırnak içindeki satır sonları desteklenmez; kaçışlı tırnaklar (`""`)
+/// çift sayıldığından tek sayıda tırnak kapanmamış bir alan demektir.
+fn has_unterminated_quote(line: &str) -> bool {
+    line.bytes().filter(|b| *b == b'"').count() % 2 == 1
+}
+
+/// Tek bir CSV satırını alanlarına ayırır (tırnaklı alanlar desteklenir)
+pub fn parse_csv_record(line: &str) -> Result<Vec<String>, String> {
+    if has_unterminated_quote(line) {
+        return Err("Tırnaklı alan satır sonunda kapanmıyor; alanlarda satır sonu desteklenmez".to_string());
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(line.as_bytes());
+    match reader.records().next() {
+        Some(Ok(record)) => Ok(record.iter().map(|field| field.trim().to_string()).collect()),
+        Some(Err(e)) => Err(e.to_string()),
+        None => Ok(vec![]),
+    }
+}
+
+/// CSV başlık satırındaki sütunların konumları
+#[derive(Debug)]
+pub struct CsvHeader {
+    name: usize,
+    email: usize,
+    latitude: Option<usize>,
+    longitude: Option<usize>,
+    location_precision: Option<usize>,
+}
+
+impl CsvHeader {
+    /// `name` ve `email` zorunludur; bilinmeyen sütunlar yok sayılır
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let columns = parse_csv_record(line.trim_start_matches('\u{feff}'))?;
+        let position = |column: &str| columns.iter().position(|c| c.eq_ignore_ascii_case(column));
+
+        let missing: Vec<&str> = ["name", "email"].into_iter().filter(|c| position(c).is_none()).collect();
+        if !missing.is_empty() {
+            return Err(format!("CSV başlığında zorunlu sütunlar eksik: {}", missing.join(", ")));
+        }
+
+        Ok(Self {
+            name: position("name").unwrap_or_default(),
+            email: position("email").unwrap_or_default(),
+            latitude: position("latitude"),
+            longitude: position("longitude"),
+            location_precision: position("location_precision"),
+        })
+    }
+
+    /// CSV satırını doğrulanmamış bir kullanıcıya dönüştürür
+    pub fn to_user(&self, line: &str) -> Result<CreateUser, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if has_unterminated_quote(line) {
+            errors.add("row", "multiline_field", "Tırnaklı alan satır sonunda kapanmıyor; alanlarda satır sonu desteklenmez");
+            return Err(errors);
+        }
+        let fields = match parse_csv_record(line) {
+            Ok(fields) => fields,
+            Err(e) => {
+                errors.add("row", "malformed_row", e);
+                return Err(errors);
+            }
+        };
+
+        let field = |index: usize| fields.get(index).map(String::as_str).unwrap_or_default();
+        let mut coordinate = |name: &str, index: Option<usize>| {
+            let value = index.map(field).unwrap_or_default();
+            if value.is_empty() {
+                return None;
+            }
+            match value.parse::<f64>() {
+                Ok(number) => Some(number),
+                Err(_) => {
+                    errors.add(name, "invalid_number", "Sayı olmalı");
+                    None
+                }
+            }
+        };
+        let latitude = coordinate("latitude", self.latitude);
+        let longitude = coordinate("longitude", self.longitude);
+        let location_precision = match self.location_precision.map(field).unwrap_or_default() {
+            "" => None,
+            value => match value.parse() {
+                Ok(precision) => Some(precision),
+                Err(_) => {
+                    errors.add("location_precision", "invalid_choice", "exact, neighbourhood, city veya none olmalı");
+                    None
+                }
+            },
+        };
+
+        errors.into_result()?;
+        Ok(CreateUser {
+            name: field(self.name).to_string(),
+            email: field(self.email).to_string(),
+            latitude,
+            longitude,
+            location_precision,
+        })
+    }
+}
+
+/// NDJSON satırını doğrulanmamış bir kullanıcıya dönüştürür
+pub fn ndjson_to_user(line: &str) -> Result<CreateUser, ValidationErrors> {
+    serde_json::from_str(line).map_err(|e| {
+        let mut errors = ValidationErrors::default();
+        errors.add("row", "malformed_row", e.to_string());
+        errors
+    })
+}
+
+/// Satırları doğrulayıp toplu halde ekleyen ve raporu biriktiren durum
+pub struct UserImporter<'a> {
+    db: &'a Database,
+    actor: &'a str,
+    format: BulkFormat,
+    csv_header: Option<CsvHeader>,
+    seen_emails: HashSet<String>,
+    batch: Vec<(u64, CreateUser)>,
+    /// Son başarıyla eklenen grubun son satırı
+    committed_through_row: u64,
+    report: ImportReport,
+}
+
+impl<'a> UserImporter<'a> {
+    pub fn new(db: &'a Database, actor: &'a str, format: BulkFormat, dry_run: bool) -> Self {
+        Self {
+            db,
+            actor,
+            format,
+            csv_header: None,
+            seen_emails: HashSet::new(),
+            batch: Vec::with_capacity(IMPORT_BATCH_SIZE),
+            committed_through_row: 0,
+            report: ImportReport {
+                dry_run,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn reject(&mut self, row: u64, errors: ValidationErrors) {
+        self.report.failed += 1;
+        if self.report.errors.len() < MAX_REPORTED_ROW_ERRORS {
+            self.report.errors.push(ImportRowError { row, errors: errors.errors });
+        } else {
+            self.report.errors_truncated = true;
+        }
+    }
+
+    fn reject_email_taken(&mut self, row: u64) {
+        let mut errors = ValidationErrors::default();
+        errors.add("email", "email_taken", "Bu email adresi zaten kullanımda");
+        self.reject(row, errors);
+    }
+
+    /// Gövdenin bir satırını işler; boş satırlar atlanır, CSV'de ilk satır başlıktır
+    pub async fn push_line(&mut self, row: u64, line: Result<Vec<u8>, LineTooLong>) -> Result<(), AppError> {
+        let line = match line {
+            Ok(line) => line,
+            Err(LineTooLong) if self.format == BulkFormat::Csv && self.csv_header.is_none() => {
+                return Err(AppError::bad_request(
+                    "invalid_csv_header",
+                    format!("CSV başlığı {} bayttan uzun olamaz", MAX_LINE_BYTES),
+                ));
+            }
+            Err(LineTooLong) => {
+                let mut errors = ValidationErrors::default();
+                errors.add("row", "line_too_long", format!("Satır {} bayttan uzun olamaz", MAX_LINE_BYTES));
+                return self.push(row, Err(errors)).await;
+            }
+        };
+        let text = match std::str::from_utf8(&line) {
+            Ok(text) => text,
+            Err(_) if self.format == BulkFormat::Csv && self.csv_header.is_none() => {
+                return Err(AppError::bad_request("invalid_csv_header", "CSV başlığı UTF-8 değil"));
+            }
+            Err(_) => {
+                let mut errors = ValidationErrors::default();
+                errors.add("row", "invalid_encoding", "Satır UTF-8 değil");
+                return self.push(row, Err(errors)).await;
+            }
+        };
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+
+        let user = match (self.format, &self.csv_header) {
+            (BulkFormat::Csv, None) => {
+                let header = CsvHeader::parse(text).map_err(|e| AppError::bad_request("invalid_csv_header", e))?;
+                self.csv_header = Some(header);
+                return Ok(());
+            }
+            (BulkFormat::Csv, Some(header)) => header.to_user(text),
+            (BulkFormat::Ndjson, _) => ndjson_to_user(text),
+        };
+        self.push(row, user).await
+    }
+
+    /// Ayrıştırılmış bir satırı doğrular ve eklenecekler arasına alır
+    async fn push(&mut self, row: u64, user: Result<CreateUser, ValidationErrors>) -> Result<(), AppError> {
+        self.report.total_rows += 1;
+
+        let user = match user.and_then(|user| user.validate().map(|_| user)) {
+            Ok(user) => user,
+            Err(errors) => {
+                self.reject(row, errors);
+                return Ok(());
+            }
+        };
+
+        // Aynı dosyada tekrar eden email'ler
+        if !self.seen_emails.insert(user.email.clone()) {
+            let mut errors = ValidationErrors::default();
+            errors.add("email", "duplicate_in_file", "Bu email adresi dosyada daha önce geçti");
+            self.reject(row, errors);
+            return Ok(());
+        }
+
+        self.batch.push((row, user));
+        if self.batch.len() >= IMPORT_BATCH_SIZE {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Bekleyen grubu ekler
+    pub async fn flush(&mut self) -> Result<(), AppError> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.batch);
+
+        let emails: Vec<String> = batch.iter().map(|(_, user)| user.email.clone()).collect();
+        let existing = self.db.existing_user_emails(&emails).await?;
+        let (taken, fresh): (Vec<_>, Vec<_>) = batch.into_iter().partition(|(_, user)| existing.contains(&user.email));
+        for (row, _) in taken {
+            self.reject_email_taken(row);
+        }
+
+        if self.report.dry_run {
+            self.report.imported += fresh.len() as u64;
+            return Ok(());
+        }
+
+        let rows: Vec<(u64, String)> = fresh.iter().map(|(row, user)| (*row, user.email.clone())).collect();
+        let inserted = self
+            .db
+            .import_users(fresh.into_iter().map(|(_, user)| user).collect(), self.actor)
+            .await?;
+        self.report.imported += inserted.len() as u64;
+        self.committed_through_row = rows.last().map_or(self.committed_through_row, |(row, _)| *row);
+
+        // Kontrol ile ekleme arasında başka bir istekle alınan email'ler
+        if inserted.len() < rows.len() {
+            let inserted_emails: HashSet<&str> = inserted.iter().map(|user| user.email.as_str()).collect();
+            for (row, email) in &rows {
+                if !inserted_emails.contains(email.as_str()) {
+                    self.reject_email_taken(*row);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn finish(mut self) -> Result<ImportReport, AppError> {
+        self.flush().await?;
+        Ok(self.into_report())
+    }
+
+    /// İçe aktarma yarıda kesildiğinde o ana kadarki raporu döndürür
+    ///
+    /// Hiçbir grup eklenmediyse (veya deneme modundaysa) veritabanında değişiklik yoktur ve hata olduğu gibi
+    /// döner. Aksi halde eklenen gruplar geri alınmadığı için rapor, `committed_through_row` satırına kadarki
+    /// geçerli satırların eklendiğini bildiren `aborted` alanıyla birlikte döner.
+    pub fn abort(mut self, error: AppError) -> Result<ImportReport, AppError> {
+        if self.report.dry_run || self.committed_through_row == 0 {
+            return Err(error);
+        }
+        tracing::error!(error = %error, committed_through_row = self.committed_through_row, "Toplu içe aktarma yarıda kesildi");
+        self.report.aborted = Some(ImportAborted {
+            code: error.code().to_string(),
+            committed_through_row: self.committed_through_row,
+        });
+        Ok(self.into_report())
+    }
+
+    fn into_report(mut self) -> ImportReport {
+        // Veritabanı çakışmaları toplu eklemede bulunduğu için hatalar satır sırasına göre dizilir
+        self.report.errors.sort_by_key(|error| error.row);
+        self.report
+    }
+}
+
+/// Dışa aktarılan CSV'nin sütunları; içe aktarma bilinmeyen sütunları yok saydığı için aynı dosya geri yüklenebilir
+const USER_CSV_COLUMNS: [&str; 11] = [
+    "id", "name", "email", "latitude", "longitude", "h3_index", "location_precision", "created_at", "updated_at",
+    "deleted_at", "erased_at",
+];
+
+/// Dışa aktarmada CSV satırı (alan sırası `USER_CSV_COLUMNS` ile aynı)
+#[derive(Serialize)]
+struct UserCsvRow<'a> {
+    id: String,
+    name: &'a str,
+    email: &'a str,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    h3_index: Option<&'a str>,
+    location_precision: &'static str,
+    created_at: String,
+    updated_at: String,
+    deleted_at: Option<String>,
+    erased_at: Option<String>,
+}
+
+/// Bir sayfa kullanıcıyı istenen formatta kodlar; CSV başlığı yalnızca ilk sayfada yazılır
+pub fn encode_users(users: &[User], format: BulkFormat, with_header: bool) -> Result<Bytes, AppError> {
+    let encoded = match format {
+        BulkFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(vec![]);
+            if with_header {
+                writer
+                    .write_record(USER_CSV_COLUMNS)
+                    .map_err(|e| AppError::Internal(format!("CSV yazılamadı: {}", e)))?;
+            }
+            for user in users {
+                writer
+                    .serialize(UserCsvRow {
+                        id: user.id.to_string(),
+                        name: &user.name,
+                        email: &user.email,
+                        latitude: user.latitude,
+                        longitude: user.longitude,
+                        h3_index: user.h3_index.as_deref(),
+                        location_precision: user.location_precision.as_str(),
+                        created_at: user.created_at.to_rfc3339(),
+                        updated_at: user.updated_at.to_rfc3339(),
+                        deleted_at: user.deleted_at.map(|d| d.to_rfc3339()),
+                        erased_at: user.erased_at.map(|d| d.to_rfc3339()),
+                    })
+                    .map_err(|e| AppError::Internal(format!("CSV yazılamadı: {}", e)))?;
+            }
+            writer
+                .into_inner()
+                .map_err(|e| AppError::Internal(format!("CSV yazılamadı: {}", e)))?
+        }
+        BulkFormat::Ndjson => {
+            let mut buffer = Vec::new();
+            for user in users {
+                serde_json::to_writer(&mut buffer, &UserResponse::from(user.clone()))
+                    .map_err(|e| AppError::Internal(format!("NDJSON yazılamadı: {}", e)))?;
+                buffer.push(b'\n');
+            }
+            buffer
+        }
+    };
+    Ok(Bytes::from(encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LocationPrecision;
+
+    #[test]
+    fn test_line_splitter_across_chunks() {
+        let mut splitter = LineSplitter::default();
+        assert_eq!(splitter.push(b"name,email\r\nAhmet,ah"), vec![Ok(b"name,email".to_vec())]);
+        assert_eq!(splitter.push(b"met@example.com\n\nAyse"), vec![Ok(b"Ahmet,ahmet@example.com".to_vec()), Ok(vec![])]);
+        assert_eq!(splitter.finish(), Some(Ok(b"Ayse".to_vec())));
+
+        // Sınırı aşan satır parçalar halinde gelse de tek bir hata olarak raporlanır ve sonraki satır etkilenmez
+        let mut splitter = LineSplitter::default();
+        let chunk = vec![b'x'; MAX_LINE_BYTES];
+        assert!(splitter.push(&chunk).is_empty());
+        assert!(splitter.push(&chunk).is_empty());
+        assert_eq!(splitter.push(b"x\nA,a@example.com\n"), vec![Err(LineTooLong), Ok(b"A,a@example.com".to_vec())]);
+        assert_eq!(splitter.push(&chunk), vec![]);
+        assert_eq!(splitter.push(b"\r\n"), vec![Ok(chunk)]);
+        assert_eq!(splitter.finish(), None);
+    }
+
+    #[test]
+    fn test_csv_row_to_user() {
+        let header = CsvHeader::parse("id,Email,name,latitude,longitude").unwrap();
+        let user = header.to_user(r#"x,ayse@example.com,"Kaya, Ayşe",41.0,"#).unwrap();
+        assert_eq!(user.name, "Kaya, Ayşe");
+        assert_eq!(user.email, "ayse@example.com");
+        assert_eq!((user.latitude, user.longitude), (Some(41.0), None));
+
+        let errors = header.to_user("x,a@example.com,A,kuzey,29").unwrap_err();
+        assert_eq!(errors.errors[0].code, "invalid_number");
+        assert!(CsvHeader::parse("name,latitude").is_err());
+
+        // Tırnak içindeki satır sonu iki bozuk satıra bölünür; ikisi de açıkça reddedilir
+        let header = CsvHeader::parse("name,email").unwrap();
+        assert_eq!(header.to_user(r#""Kaya"#).unwrap_err().errors[0].code, "multiline_field");
+        assert_eq!(header.to_user(r#"Ayşe",ayse@example.com"#).unwrap_err().errors[0].code, "multiline_field");
+        assert!(header.to_user(r#""Kaya ""Ayşe""",ayse@example.com"#).is_ok());
+
+        let header = CsvHeader::parse("name,email,location_precision").unwrap();
+        assert_eq!(header.to_user("A,a@example.com,city").unwrap().location_precision, Some(LocationPrecision::City));
+        assert_eq!(header.to_user("A,a@example.com,").unwrap().location_precision, None);
+        assert_eq!(header.to_user("A,a@example.com,street").unwrap_err().errors[0].code, "invalid_choice");
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::web;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::h3_utils::{calculate_distance_km, cells_to_multipolygon, coords_to_h3, h3_to_coords};
+use crate::models::{CatchmentJob, CatchmentReport, CoverageGap, GeoJsonMultiPolygon, JobState, Port, PortCatchment, TimeRange};
+
+/// Her kullanıcıyı en yakın limana atayıp limanların etki alanlarını hesaplar
+///
+/// Her kullanıcı kendi koordinatından Haversine mesafesiyle en yakın limana atanır. Bir limanın etki alanı, ona
+/// atanan kullanıcıların `resolution` çözünürlüğündeki H3 hücrelerinin birleşimidir; sınırdaki bir hücrenin
+/// kullanıcıları farklı limanlara atanabildiği için komşu etki alanları bu hücrelerde örtüşebilir. Bir limana
+/// atanan kullanıcılardan `min_count`'tan azının düştüğü hücreler etki alanına çizilmez; tek kişilik bir hücre o
+/// kişinin konumunu ele verir. Aynı nedenle `min_count`'tan az kullanıcısı olan limanların kullanıcı sayısı ve
+/// uzaklıkları boş döner; bu limanlar yine de kullanıcı sayısına göre sıralanır.
+pub fn compute(ports: &[Port], users: &[(f64, f64)], port_type: Option<String>, resolution: u8, min_count: i64) -> CatchmentReport {
+    let mut assigned: Vec<(HashMap<String, i64>, Vec<f64>)> = vec![(HashMap::new(), Vec::new()); ports.len()];
+    let mut unassigned = users.len() as i64;
+    for &(lat, lng) in users {
+        let Some((nearest, distance)) = nearest_to_point(ports, lat, lng) else {
+            continue;
+        };
+
+        let (port_cells, distances) = &mut assigned[nearest];
+        if let Ok(cell) = coords_to_h3(lat, lng, resolution) {
+            *port_cells.entry(cell).or_default() += 1;
+        }
+        distances.push(distance);
+        unassigned -= 1;
+    }
+
+    let mut catchments: Vec<(i64, PortCatchment)> = ports
+        .iter()
+        .zip(assigned)
+        .map(|(port, (port_cells, mut distances))| {
+            distances.sort_by(f64::total_cmp);
+            let user_count = distances.len() as i64;
+            let sparse = user_count > 0 && user_count < min_count;
+            let port_cells: Vec<String> = port_cells
+                .into_iter()
+                .filter(|&(_, count)| count >= min_count)
+                .map(|(cell, _)| cell)
+                .collect();
+            let catchment = PortCatchment {
+                port_id: port.id,
+                code: port.code.clone(),
+                name: port.name.clone(),
+                port_type: port.port_type.clone(),
+                latitude: port.latitude,
+                longitude: port.longitude,
+                user_count: (!sparse).then_some(user_count),
+                median_distance_km: median(&distances).filter(|_| !sparse),
+                max_distance_km: distances.last().copied().filter(|_| !sparse),
+                cell_count: port_cells.len(),
+                catchment: GeoJsonMultiPolygon::new(cells_to_multipolygon(&port_cells).unwrap_or_default()),
+            };
+            (user_count, catchment)
+        })
+        .collect();
+    catchments.sort_by(|(a_count, a), (b_count, b)| b_count.cmp(a_count).then_with(|| a.code.cmp(&b.code)));
+    let catchments = catchments.into_iter().map(|(_, catchment)| catchment).collect();
+
+    CatchmentReport {
+        port_type,
+        resolution,
+        computed_at: Utc::now(),
+        located_users: users.len() as i64,
+        unassigned_users: unassigned,
+        ports: catchments,
+    }
+}
+
+/// En yakın limanı `max_distance_km`'den uzak olan, kullanıcısı bulunan hücreleri bulur (kalabalık önce)
+///
+/// Uzaklık hücre merkezinden en yakın limana Haversine mesafesidir; hiç liman yoksa tüm hücreler listelenir.
+/// `min_count`'tan az kullanıcısı olan boşluk hücreleri listelenmez; dönen ikinci değer bu hücrelerdeki toplam
+/// kullanıcı sayısıdır.
+pub fn coverage_gaps(ports: &[Port], users: &[(f64, f64)], resolution: u8, max_distance_km: f64, min_count: i64) -> (Vec<CoverageGap>, i64) {
+    let mut suppressed_users = 0;
+    let mut gaps: Vec<CoverageGap> = group_by_cell(users, resolution)
+        .into_iter()
+        .filter_map(|(cell, locations)| {
+            let (center_latitude, center_longitude) = h3_to_coords(&cell).ok()?;
+            let nearest = nearest_to_cell(ports, &cell);
+            if nearest.is_some_and(|(_, distance)| distance <= max_distance_km) {
+                return None;
+            }
+            if (locations.len() as i64) < min_count {
+                suppressed_users += locations.len() as i64;
+                return None;
+            }
+            Some(CoverageGap {
+                h3_index: cell,
+                user_count: locations.len() as i64,
+                center_latitude,
+                center_longitude,
+                nearest_port_id: nearest.map(|(i, _)| ports[i].id),
+                nearest_port_code: nearest.map(|(i, _)| ports[i].code.clone()),
+                nearest_port_distance_km: nearest.map(|(_, distance)| distance),
+            })
+        })
+        .collect();
+    gaps.sort_by(|a, b| b.user_count.cmp(&a.user_count).then_with(|| a.h3_index.cmp(&b.h3_index)));
+    (gaps, suppressed_users)
+}
+
+// Kullanıcıları verilen çözünürlükteki H3 hücrelerine gruplar
+fn group_by_cell(users: &[(f64, f64)], resolution: u8) -> HashMap<String, Vec<(f64, f64)>> {
+    let mut cells: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+    for &(lat, lng) in users {
+        if let Ok(cell) = coords_to_h3(lat, lng, resolution) {
+            cells.entry(cell).or_default().push((lat, lng));
+        }
+    }
+    cells
+}
+
+// Hücre merkezine en yakın limanın sırası ve uzaklığı (km)
+fn nearest_to_cell(ports: &[Port], cell: &str) -> Option<(usize, f64)> {
+    let (lat, lng) = h3_to_coords(cell).ok()?;
+    nearest_to_point(ports, lat, lng)
+}
+
+// Koordinata en yakın limanın sırası ve uzaklığı (km)
+fn nearest_to_point(ports: &[Port], lat: f64, lng: f64) -> Option<(usize, f64)> {
+    ports
+        .iter()
+        .map(|port| calculate_distance_km(lat, lng, port.latitude, port.longitude))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Sıralı değerlerin medyanı
+fn median(sorted: &[f64]) -> Option<f64> {
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+/// Önbellekteki rapor veya hesaplanmakta olan işin durumu
+pub enum CatchmentLookup {
+    Ready(Arc<CatchmentReport>),
+    Pending(CatchmentJob),
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    job: Option<CatchmentJob>,
+    report: Option<Arc<CatchmentReport>>,
+}
+
+/// Liman tipi ve çözünürlük başına hesaplanan etki alanı raporlarının önbelleği
+///
+/// Rapor `ttl` süresince güncel sayılır; süresi dolduğunda veya yenileme istendiğinde arka planda yeniden
+/// hesaplanır. Aynı anahtar için aynı anda tek hesaplama çalışır.
+pub struct CatchmentCache {
+    ttl: Duration,
+    min_count: i64,
+    entries: Mutex<HashMap<(Option<String>, u8), CacheEntry>>,
+}
+
+impl CatchmentCache {
+    pub fn new(ttl: Duration, min_count: i64) -> Self {
+        Self { ttl, min_count, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Güncel rapor varsa döndürür; yoksa (veya `refresh` ise) hesaplamayı başlatıp işin durumunu döndürür
+    pub fn get_or_start(
+        cache: web::Data<Self>,
+        db: web::Data<Database>,
+        port_type: Option<String>,
+        resolution: u8,
+        refresh: bool,
+    ) -> CatchmentLookup {
+        let key = (port_type.clone(), resolution);
+        let job = {
+            let mut entries = cache.entries.lock().unwrap();
+            let entry = entries.entry(key.clone()).or_default();
+            if let Some(job) = entry.job.as_ref().filter(|job| job.state == JobState::Running) {
+                return CatchmentLookup::Pending(job.clone());
+            }
+            if let Some(report) = entry.report.as_ref().filter(|_| !refresh) {
+                let age = (Utc::now() - report.computed_at).to_std().unwrap_or_default();
+                if age < cache.ttl {
+                    return CatchmentLookup::Ready(report.clone());
+                }
+            }
+
+            let job = CatchmentJob {
+                id: Uuid::new_v4(),
+                state: JobState::Running,
+                port_type,
+                resolution,
+                started_at: Utc::now(),
+                finished_at: None,
+                error: entry.job.as_ref().and_then(|job| job.error.clone()),
+            };
+            entry.job = Some(job.clone());
+            job
+        };
+
+        let started = job.clone();
+        actix_web::rt::spawn(async move {
+            let result = load_and_compute(&db, job.port_type.clone(), job.resolution, cache.min_count).await;
+
+            let mut entries = cache.entries.lock().unwrap();
+            let entry = entries.entry(key).or_default();
+            let mut job = job;
+            job.finished_at = Some(Utc::now());
+            match result {
+                Ok(report) => {
+                    job.state = JobState::Completed;
+                    job.error = None;
+                    entry.report = Some(Arc::new(report));
+                }
+                Err(e) => {
+                    tracing::error!(job_id = %job.id, error = %e, "Liman etki alanları hesaplanamadı");
+                    job.state = JobState::Failed;
+                    job.error = Some(e);
+                }
+            }
+            entry.job = Some(job);
+        });
+        CatchmentLookup::Pending(started)
+    }
+}
+
+/// Silinmemiş limanları (verilirse yalnızca `port_type` tipindekileri) ve konumlu kullanıcıları yükler
+pub async fn load_inputs(db: &Database, port_type: Option<&str>) -> Result<(Vec<Port>, Vec<(f64, f64)>), sqlx::Error> {
+    let mut ports = db.get_all_ports(false).await?;
+    if let Some(port_type) = port_type {
+        ports.retain(|port| port.port_type == port_type);
+    }
+    let users = db.get_user_locations(TimeRange::default()).await?;
+    Ok((ports, users))
+}
+
+async fn load_and_compute(db: &Database, port_type: Option<String>, resolution: u8, min_count: i64) -> Result<CatchmentReport, String> {
+    let (ports, users) = load_inputs(db, port_type.as_deref()).await.map_err(|e| e.to_string())?;
+
+    let report = web::block(move || compute(&ports, &users, port_type, resolution, min_count))
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        port_type = report.port_type.as_deref().unwrap_or("*"),
+        resolution,
+        ports = report.ports.len(),
+        located_users = report.located_users,
+        "Liman etki alanları hesaplandı"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(code: &str, latitude: f64, longitude: f64) -> Port {
+        Port {
+            id: Uuid::new_v4(),
+            name: code.to_string(),
+            code: code.to_string(),
+            country: "Turkey".to_string(),
+            city: code.to_string(),
+            latitude,
+            longitude,
+            h3_index: coords_to_h3(latitude, longitude, 7).unwrap(),
+            port_type: "container".to_string(),
+            capacity: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_users_are_assigned_to_nearest_port() {
+        let ports = [port("TRIST", 41.0, 29.0), port("TRIZM", 38.4, 27.1), port("TRMER", 36.8, 34.6)];
+        let users = [(41.01, 28.98), (41.05, 29.02), (40.95, 29.1), (38.42, 27.14)];
+
+        let report = compute(&ports, &users, None, 6, 1);
+        assert_eq!(report.located_users, 4);
+        assert_eq!(report.unassigned_users, 0);
+
+        let codes: Vec<&str> = report.ports.iter().map(|p| p.code.as_str()).collect();
+        assert_eq!(codes, ["TRIST", "TRIZM", "TRMER"]);
+        assert_eq!(report.ports[0].user_count, Some(3));
+        assert!(report.ports[0].median_distance_km.unwrap() < 15.0);
+        assert!(!report.ports[0].catchment.coordinates.is_empty());
+
+        // Kullanıcısı olmayan liman boş bir etki alanıyla listelenir
+        assert_eq!(report.ports[2].user_count, Some(0));
+        assert_eq!(report.ports[2].median_distance_km, None);
+        assert!(report.ports[2].catchment.coordinates.is_empty());
+    }
+
+    #[test]
+    fn test_users_are_assigned_by_their_own_coordinates() {
+        // Kaba çözünürlükte aynı hücreye düşen iki kullanıcı farklı limanlara daha yakın
+        let (lat, lng) = h3_to_coords(&coords_to_h3(41.0, 29.0, 2).unwrap()).unwrap();
+        let ports = [port("WEST", lat, lng - 1.0), port("EAST", lat, lng + 1.0)];
+        let users = [(lat, lng - 0.3), (lat, lng + 0.3)];
+        assert_eq!(coords_to_h3(users[0].0, users[0].1, 2).unwrap(), coords_to_h3(users[1].0, users[1].1, 2).unwrap());
+
+        let report = compute(&ports, &users, None, 2, 1);
+        assert!(report.ports.iter().all(|p| p.user_count == Some(1)), "{:?}", report.ports);
+        assert!(report.ports.iter().all(|p| p.max_distance_km.unwrap() < 60.0));
+    }
+
+    #[test]
+    fn test_users_are_unassigned_without_ports() {
+        let users = [(41.01, 28.98), (38.42, 27.14)];
+        assert_eq!(compute(&[], &users, None, 6, 1).unassigned_users, 2);
+    }
+
+    #[test]
+    fn test_sparse_cells_are_left_out_of_catchments() {
+        let ports = [port("TRIST", 41.0, 29.0), port("TRIZM", 38.4, 27.1)];
+        let users = [(41.01, 28.98), (41.011, 28.981), (38.42, 27.14)];
+
+        // İzmir'deki tek kullanıcının hücresi etki alanına çizilmez
+        let report = compute(&ports, &users, None, 6, 2);
+        assert_eq!(report.ports[0].code, "TRIST");
+        assert_eq!(report.ports[0].cell_count, 1);
+        assert_eq!(report.ports[1].code, "TRIZM");
+        assert_eq!(report.ports[1].cell_count, 0);
+        assert!(report.ports[1].catchment.coordinates.is_empty());
+    }
+
+    #[test]
+    fn test_sparse_catchments_hide_counts_and_distances() {
+        let ports = [port("TRIST", 41.0, 29.0), port("TRIZM", 38.4, 27.1), port("TRMER", 36.8, 34.6)];
+        let users = [(41.01, 28.98), (41.011, 28.981), (38.42, 27.14)];
+
+        // İzmir'e atanan tek kullanıcının sayısı ve limana uzaklığı yanıtta yer almaz
+        let report = compute(&ports, &users, None, 6, 2);
+        let codes: Vec<&str> = report.ports.iter().map(|p| p.code.as_str()).collect();
+        assert_eq!(codes, ["TRIST", "TRIZM", "TRMER"]);
+        assert_eq!(report.ports[0].user_count, Some(2));
+        assert!(report.ports[0].max_distance_km.is_some());
+        assert_eq!(report.ports[1].user_count, None);
+        assert_eq!(report.ports[1].median_distance_km, None);
+        assert_eq!(report.ports[1].max_distance_km, None);
+        assert_eq!(report.ports[2].user_count, Some(0));
+    }
+
+    #[test]
+    fn test_coverage_gaps_are_found() {
+        let ports = [port("TRIST", 41.0, 29.0), port("TRIZM", 38.4, 27.1), port("TRMER", 36.8, 34.6)];
+        // İzmir'deki kullanıcı limana yakın, Ankara'daki kullanıcılar 100 km eşiğinin dışında
+        let users = [(38.42, 27.14), (39.93, 32.86), (39.94, 32.85)];
+
+        let (gaps, suppressed_users) = coverage_gaps(&ports, &users, 6, 100.0, 1);
+        assert_eq!(suppressed_users, 0);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].user_count, 2);
+        assert_eq!(gaps[0].nearest_port_code.as_deref(), Some("TRIST"));
+        assert!(gaps[0].nearest_port_distance_km.unwrap() > 100.0);
+    }
+
+    #[test]
+    fn test_sparse_coverage_gaps_are_suppressed() {
+        let ports = [port("TRIST", 41.0, 29.0)];
+        // Ankara'da 3 kullanıcı aynı hücrede, Kayseri'de tek kullanıcı
+        let users = [(39.93, 32.86), (39.94, 32.85), (39.93, 32.85), (38.72, 35.48)];
+
+        let (gaps, suppressed_users) = coverage_gaps(&ports, &users, 6, 100.0, 3);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].user_count, 3);
+        assert_eq!(suppressed_users, 1);
+    }
+}
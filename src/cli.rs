@@ -0,0 +1,319 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+
+use crate::config::{self, AppConfig};
+use crate::crypto::EmailCipher;
+use crate::database::{self, Database, MIGRATOR};
+use crate::doctor::{self, Severity};
+use crate::h3_audit;
+use crate::models::{H3AuditCounts, HealthStatus, JobState};
+use crate::seed;
+use crate::setup;
+
+/// H3 tabanlı kullanıcı ve liman servisi
+#[derive(Debug, Parser)]
+#[command(name = "rustmicro", version)]
+pub struct Cli {
+    /// Konfigürasyon dosyası; verilmezse CONFIG_FILE, o da yoksa çalışma dizinindeki config.toml (varsa)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// HTTP sunucusunu başlatır (komut verilmezse varsayılan)
+    Serve,
+    /// Bekleyen veritabanı migrasyonlarını uygular
+    Migrate,
+    /// Veritabanı yönetimi
+    #[command(subcommand)]
+    Db(DbCommand),
+    /// Örnek veri setlerini yükler (tekrar çalıştırılabilir)
+    Seed {
+        /// Yüklenecek setler (verilmezse tümü)
+        sets: Vec<String>,
+        /// Setleri listeler, veritabanına dokunmaz
+        #[arg(long)]
+        list: bool,
+        /// Aynı sürümü yüklenmiş setleri de yeniden yükler (mevcut kayıtlar yine atlanır)
+        #[arg(long)]
+        force: bool,
+        /// Eski 001-003 migrasyonlarının eklediği, hiç değiştirilmemiş örnek kayıtları siler; set yüklemez
+        #[arg(long, conflicts_with_all = ["sets", "list", "force"])]
+        remove_legacy_samples: bool,
+    },
+    /// Kullanıcı ve limanların h3_index'ini koordinatlarıyla karşılaştırır; uyuşmazlık kalırsa 1 ile çıkar
+    H3Audit {
+        /// Uyuşmayan index'leri koordinatlardan yeniden hesaplayarak düzeltir
+        #[arg(long)]
+        fix: bool,
+        /// Tek transaction'da işlenecek kayıt sayısı
+        #[arg(long, default_value_t = h3_audit::DEFAULT_BATCH_SIZE, value_parser = clap::value_parser!(u32).range(1..=i64::from(h3_audit::MAX_BATCH_SIZE)))]
+        batch_size: u32,
+    },
+    /// Konfigürasyonu, veritabanını ve bağımlılıkları kontrol eder; sorunlar için öneri verir
+    Doctor,
+    /// Etkin konfigürasyonu gizli değerler maskelenmiş olarak TOML biçiminde yazdırır
+    Config,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Konfigürasyondaki veritabanını yoksa oluşturur
+    Create,
+}
+
+impl Cli {
+    /// Okunacak konfigürasyon dosyası: `--config` bayrağı, yoksa boş olmayan `CONFIG_FILE`
+    pub fn config_file(&self) -> Option<PathBuf> {
+        self.config
+            .clone()
+            .or_else(|| std::env::var_os("CONFIG_FILE").filter(|v| !v.is_empty()).map(PathBuf::from))
+    }
+}
+
+/// Sunucu dışındaki bir yönetim komutunu çalıştırır ve süreç çıkış kodunu döndürür
+pub async fn run(command: Command, config_file: Option<&Path>) -> i32 {
+    let result = match command {
+        Command::Serve => unreachable!("serve main içinde çalıştırılır"),
+        Command::Migrate => migrate(config_file).await,
+        Command::Db(DbCommand::Create) => create_database(config_file).await,
+        Command::Seed { remove_legacy_samples: true, .. } => remove_legacy_samples(config_file).await,
+        Command::Seed { sets, list, force, .. } => seed(config_file, &sets, list, force).await,
+        Command::H3Audit { fix, batch_size } => return audit_h3(config_file, fix, batch_size).await,
+        Command::Doctor => return doctor(config_file).await,
+        Command::Config => return print_config(config_file),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+/// Konfigürasyonu yükler; hatalıysa tüm hataları yazdırıp süreci sonlandırır
+pub fn load_config(config_file: Option<&Path>) -> AppConfig {
+    match AppConfig::load(config_file) {
+        Ok(config) => config,
+        Err(e) => {
+            eprint!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn migrate(config_file: Option<&Path>) -> Result<(), String> {
+    let config = load_config(config_file);
+    let pool = setup::connect(&config).await.map_err(|e| format!("Veritabanına bağlanılamadı: {}", e))?;
+
+    let before = database::migration_status(&pool)
+        .await
+        .map_err(|e| format!("Migrasyon durumu okunamadı: {}", e))?;
+    MIGRATOR.run(&pool).await.map_err(|e| format!("Migrasyon hatası: {}", e))?;
+
+    if before.pending.is_empty() {
+        println!("✅ Şema güncel ({} migrasyon uygulanmış)", before.expected);
+    } else {
+        for migration in MIGRATOR.iter().filter(|m| before.pending.contains(&m.version)) {
+            println!("   {} {}", migration.version, migration.description);
+        }
+        println!("✅ {} migrasyon uygulandı", before.pending.len());
+    }
+    Ok(())
+}
+
+async fn create_database(config_file: Option<&Path>) -> Result<(), String> {
+    let config = load_config(config_file);
+    let name = config.redacted().database.url;
+
+    match setup::create_database(&config).await {
+        Ok(true) => println!("✅ Veritabanı oluşturuldu: {}", name),
+        Ok(false) => println!("✅ Veritabanı zaten mevcut: {}", name),
+        Err(e) => return Err(format!("Veritabanı oluşturulamadı ({}): {}", name, e)),
+    }
+    Ok(())
+}
+
+// Şeması güncel veritabanına bağlanır
+async fn connect_database(config: &AppConfig) -> Result<Database, String> {
+    let cipher = EmailCipher::from_config(config)?;
+    let pool = setup::connect(config).await.map_err(|e| format!("Veritabanına bağlanılamadı: {}", e))?;
+
+    let status = database::migration_status(&pool)
+        .await
+        .map_err(|e| format!("Migrasyon durumu okunamadı: {}", e))?;
+    if status.status != HealthStatus::Up {
+        return Err("Şema güncel değil; önce migrasyonları uygulayın: rustmicro migrate".to_string());
+    }
+    Ok(Database::new(pool, cipher))
+}
+
+async fn seed(config_file: Option<&Path>, names: &[String], list: bool, force: bool) -> Result<(), String> {
+    let sets = seed::fixture_sets()?;
+    if list {
+        for set in &sets {
+            println!(
+                "{} (v{}): {} — {} kullanıcı, {} liman",
+                set.name,
+                set.version,
+                set.description,
+                set.users.len(),
+                set.ports.len()
+            );
+        }
+        return Ok(());
+    }
+    if let Some(unknown) = names.iter().find(|name| !sets.iter().any(|set| &set.name == *name)) {
+        let available: Vec<&str> = sets.iter().map(|set| set.name.as_str()).collect();
+        return Err(format!("Bilinmeyen set: {} (mevcut setler: {})", unknown, available.join(", ")));
+    }
+
+    let config = load_config(config_file);
+    let db = connect_database(&config).await?;
+
+    // Örneklerin email'e göre bulunabilmesi için düz metin email'ler önce kör indeksle şifrelenir
+    db.reencrypt_emails().await.map_err(|e| format!("Email'ler şifrelenemedi: {}", e))?;
+    let loaded = db
+        .get_fixture_versions()
+        .await
+        .map_err(|e| format!("Yüklenmiş setler okunamadı: {}", e))?;
+
+    for set in sets.iter().filter(|set| names.is_empty() || names.contains(&set.name)) {
+        match loaded.get(&set.name) {
+            Some(&version) if version >= set.version && !force => {
+                println!("✅ {} (v{}) zaten yüklü", set.name, version);
+                continue;
+            }
+            _ => {}
+        }
+        let report = seed::load(&db, set)
+            .await
+            .map_err(|e| format!("{} yüklenemedi: {}", set.name, e))?;
+        println!(
+            "✅ {} (v{}) yüklendi: {} kullanıcı, {} liman eklendi ({} kullanıcı, {} liman zaten vardı)",
+            set.name,
+            set.version,
+            report.users_created,
+            report.ports_created,
+            report.users_skipped,
+            report.ports_skipped
+        );
+    }
+    Ok(())
+}
+
+async fn remove_legacy_samples(config_file: Option<&Path>) -> Result<(), String> {
+    let config = load_config(config_file);
+    let db = connect_database(&config).await?;
+
+    // Örnek kullanıcılar email'e göre bulunduğu için düz metin email'ler önce şifrelenir
+    db.reencrypt_emails().await.map_err(|e| format!("Email'ler şifrelenemedi: {}", e))?;
+    let report = seed::remove_legacy_samples(&db)
+        .await
+        .map_err(|e| format!("Örnek kayıtlar silinemedi: {}", e))?;
+    println!(
+        "✅ Eski örnek kayıtlar silindi: {} kullanıcı, {} liman ({} kullanıcı, {} liman değiştirildiği için korundu)",
+        report.users_deleted, report.ports_deleted, report.users_kept, report.ports_kept
+    );
+    Ok(())
+}
+
+async fn audit_h3(config_file: Option<&Path>, fix: bool, batch_size: u32) -> i32 {
+    let config = load_config(config_file);
+    let db = match connect_database(&config).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return 1;
+        }
+    };
+
+    let mut status = h3_audit::new_status(fix, batch_size);
+    h3_audit::run(&db, &mut status, |progress| {
+        if progress.state == JobState::Running {
+            println!(
+                "   kullanıcılar {}, limanlar {}",
+                format_progress(&progress.users),
+                format_progress(&progress.ports)
+            );
+        }
+    })
+    .await;
+
+    for mismatch in &status.mismatches {
+        println!(
+            "   {} {}: kayıtlı {} (çözünürlük {}), beklenen {}{}",
+            mismatch.entity.as_str(),
+            mismatch.id,
+            mismatch.stored.as_deref().unwrap_or("-"),
+            mismatch.stored_resolution.map_or("-".to_string(), |r| r.to_string()),
+            mismatch.expected.as_deref().unwrap_or("-"),
+            if mismatch.fixed { " → düzeltildi" } else { "" }
+        );
+    }
+    if status.mismatches_truncated {
+        println!("   ... (liste kırpıldı)");
+    }
+
+    if let Some(error) = &status.error {
+        eprintln!("❌ H3 denetimi başarısız: {}", error);
+        return 1;
+    }
+    let mismatched = status.users.mismatched + status.ports.mismatched;
+    let fixed = status.users.fixed + status.ports.fixed;
+    if mismatched == fixed {
+        println!(
+            "✅ {} kullanıcı, {} liman denetlendi (çözünürlük {}): {} uyuşmazlık, {} düzeltildi",
+            status.users.checked, status.ports.checked, status.resolution, mismatched, fixed
+        );
+        0
+    } else {
+        println!(
+            "❌ {} kullanıcı, {} liman denetlendi (çözünürlük {}): {} uyuşmazlık, {} düzeltildi",
+            status.users.checked, status.ports.checked, status.resolution, mismatched, fixed
+        );
+        if !fix {
+            println!("   → Düzeltmek için: rustmicro h3-audit --fix");
+        }
+        1
+    }
+}
+
+fn format_progress(counts: &H3AuditCounts) -> String {
+    format!("{}/{} (uyuşmayan {}, düzeltilen {})", counts.checked, counts.total, counts.mismatched, counts.fixed)
+}
+
+async fn doctor(config_file: Option<&Path>) -> i32 {
+    let diagnostics = doctor::run(config_file).await;
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    let warnings = diagnostics.iter().filter(|d| d.severity == Severity::Warning).count();
+    println!();
+    if errors == 0 && warnings == 0 {
+        println!("Sorun bulunamadı");
+    } else {
+        println!("{} hata, {} uyarı", errors, warnings);
+    }
+    i32::from(errors > 0)
+}
+
+fn print_config(config_file: Option<&Path>) -> i32 {
+    let (config, errors) = AppConfig::resolve(config_file);
+    print!("{}", config.redacted().to_toml());
+    if errors.is_empty() {
+        0
+    } else {
+        eprint!("{}", config::ConfigError { errors });
+        1
+    }
+}
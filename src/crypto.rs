@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::config::AppConfig;
+
+/// AES-GCM nonce uzunluğu (byte); şifreli veri `nonce || ciphertext` olarak saklanır
+const NONCE_LEN: usize = 12;
+
+/// Kör indeks anahtarının en az uzunluğu (byte)
+const MIN_BLIND_INDEX_KEY_LEN: usize = 32;
+
+/// Şifrelenmiş bir email ve arama için kör indeksi
+#[derive(Debug, Clone)]
+pub struct EncryptedEmail {
+    pub ciphertext: Vec<u8>,
+    pub key_id: String,
+    pub hash: String,
+}
+
+/// Email alanını AES-256-GCM ile şifreleyen ve HMAC-SHA256 kör indeksini hesaplayan anahtarlık
+///
+/// Yeni veriler her zaman etkin (listedeki ilk) anahtarla şifrelenir; eski anahtarlar yalnızca çözmek için
+/// tutulur ve yeniden şifreleme tamamlandıktan sonra konfigürasyondan kaldırılabilir. Kör indeks anahtarı
+/// döndürülemez: değişirse tüm indeksler yeniden hesaplanmalıdır. Kör indeks AES-GCM'e ek doğrulanmış veri
+/// (AAD) olarak verilir; şifreli veri başka bir email'in satırına taşınırsa çözülemez.
+pub struct EmailCipher {
+    active_key_id: String,
+    keys: HashMap<String, Aes256Gcm>,
+    blind_index_key: Vec<u8>,
+}
+
+impl EmailCipher {
+    /// `keys`: `id:base64` çiftlerinin virgülle ayrılmış listesi (ilki etkin anahtar, her biri 32 byte)
+    pub fn new(keys: &str, blind_index_key: &str) -> Result<Self, String> {
+        let mut active_key_id = None;
+        let mut parsed = HashMap::new();
+        for entry in keys.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (id, encoded) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Anahtar 'id:base64' biçiminde olmalı: {}", entry))?;
+            let id = id.trim();
+            if id.is_empty() {
+                return Err("Anahtar kimliği boş olamaz".to_string());
+            }
+            let key = BASE64
+                .decode(encoded.trim())
+                .map_err(|e| format!("'{}' anahtarı base64 olarak çözülemedi: {}", id, e))?;
+            let cipher = Aes256Gcm::new_from_slice(&key)
+                .map_err(|_| format!("'{}' anahtarı 32 byte olmalı ({} byte verildi)", id, key.len()))?;
+            if parsed.insert(id.to_string(), cipher).is_some() {
+                return Err(format!("'{}' anahtar kimliği birden fazla kez tanımlanmış", id));
+            }
+            active_key_id.get_or_insert_with(|| id.to_string());
+        }
+        let active_key_id = active_key_id.ok_or("En az bir şifreleme anahtarı tanımlanmalı")?;
+
+        let blind_index_key = BASE64
+            .decode(blind_index_key.trim())
+            .map_err(|e| format!("Kör indeks anahtarı base64 olarak çözülemedi: {}", e))?;
+        if blind_index_key.len() < MIN_BLIND_INDEX_KEY_LEN {
+            return Err(format!("Kör indeks anahtarı en az {} byte olmalı", MIN_BLIND_INDEX_KEY_LEN));
+        }
+
+        Ok(Self {
+            active_key_id,
+            keys: parsed,
+            blind_index_key,
+        })
+    }
+
+    /// Konfigürasyondaki `EMAIL_ENCRYPTION_KEYS` ve `EMAIL_BLIND_INDEX_KEY` değerlerinden oluşturur
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let (Some(keys), Some(blind_index_key)) = (&config.encryption.email_keys, &config.encryption.email_blind_index_key) else {
+            return Err("EMAIL_ENCRYPTION_KEYS ve EMAIL_BLIND_INDEX_KEY tanımlanmalı".to_string());
+        };
+        Self::new(keys, blind_index_key)
+    }
+
+    /// Yeni verilerin şifrelendiği anahtarın kimliği
+    pub fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    /// Kimliği verilen anahtar anahtarlıkta tanımlı mı
+    pub fn has_key(&self, key_id: &str) -> bool {
+        self.keys.contains_key(key_id)
+    }
+
+    /// Email'i etkin anahtarla, kör indeksine bağlı olarak şifreler
+    pub fn encrypt(&self, email: &str) -> Result<EncryptedEmail, String> {
+        let cipher = &self.keys[&self.active_key_id];
+        let hash = self.blind_index(email);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted = cipher
+            .encrypt(&nonce, Payload { msg: email.as_bytes(), aad: hash.as_bytes() })
+            .map_err(|_| "Email şifrelenemedi".to_string())?;
+
+        let mut ciphertext = Vec::with_capacity(NONCE_LEN + encrypted.len());
+        ciphertext.extend_from_slice(&nonce);
+        ciphertext.extend_from_slice(&encrypted);
+        Ok(EncryptedEmail {
+            ciphertext,
+            key_id: self.active_key_id.clone(),
+            hash,
+        })
+    }
+
+    /// `key_id` anahtarıyla ve `email_hash` kör indeksine bağlı olarak şifrelenmiş email'i çözer
+    pub fn decrypt(&self, key_id: &str, ciphertext: &[u8], email_hash: &str) -> Result<String, String> {
+        self.open(key_id, ciphertext, email_hash.as_bytes())
+    }
+
+    /// AAD kullanılmadan şifrelenmiş eski email'i çözer (yalnızca açılıştaki yeniden şifreleme için)
+    pub fn decrypt_unbound(&self, key_id: &str, ciphertext: &[u8]) -> Result<String, String> {
+        self.open(key_id, ciphertext, &[])
+    }
+
+    fn open(&self, key_id: &str, ciphertext: &[u8], aad: &[u8]) -> Result<String, String> {
+        let cipher = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| format!("Bilinmeyen şifreleme anahtarı: {}", key_id))?;
+        if ciphertext.len() < NONCE_LEN {
+            return Err("Şifreli email bozuk".to_string());
+        }
+        let (nonce, encrypted) = ciphertext.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: encrypted, aad })
+            .map_err(|_| format!("Email '{}' anahtarıyla çözülemedi", key_id))?;
+        String::from_utf8(plaintext).map_err(|_| "Çözülen email geçerli UTF-8 değil".to_string())
+    }
+
+    /// Eşitlik araması ve benzersizlik için deterministik kör indeks (hex HMAC-SHA256)
+    pub fn blind_index(&self, email: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.blind_index_key)
+            .expect("HMAC her uzunlukta anahtar kabul eder");
+        mac.update(email.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> String {
+        BASE64.encode([byte; 32])
+    }
+
+    #[test]
+    fn test_encrypt_round_trip_and_rotation() {
+        let old = EmailCipher::new(&format!("v1:{}", key(1)), &key(9)).unwrap();
+        let sealed = old.encrypt("ahmet@example.com").unwrap();
+        assert_eq!(sealed.key_id, "v1");
+        assert_ne!(old.encrypt("ahmet@example.com").unwrap().ciphertext, sealed.ciphertext);
+
+        // Döndürmeden sonra yeni veriler v2 ile şifrelenir, eski veriler hâlâ çözülebilir
+        let rotated = EmailCipher::new(&format!("v2:{}, v1:{}", key(2), key(1)), &key(9)).unwrap();
+        assert_eq!(rotated.active_key_id(), "v2");
+        assert_eq!(rotated.decrypt("v1", &sealed.ciphertext, &sealed.hash).unwrap(), "ahmet@example.com");
+        assert_eq!(rotated.blind_index("ahmet@example.com"), sealed.hash);
+        assert!(rotated.decrypt("v2", &sealed.ciphertext, &sealed.hash).is_err());
+        assert!(rotated.decrypt("v0", &sealed.ciphertext, &sealed.hash).is_err());
+
+        // Başka bir kullanıcının satırına taşınan şifreli veri çözülemez
+        let other = old.encrypt("ayse@example.com").unwrap();
+        assert!(old.decrypt("v1", &sealed.ciphertext, &other.hash).is_err());
+        assert!(old.decrypt_unbound("v1", &sealed.ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        assert!(EmailCipher::new("", &key(9)).is_err());
+        assert!(EmailCipher::new(&format!("v1:{}", BASE64.encode([1u8; 16])), &key(9)).is_err());
+        assert!(EmailCipher::new(&format!("v1:{0},v1:{0}", key(1)), &key(9)).is_err());
+        assert!(EmailCipher::new(&format!("v1:{}", key(1)), &BASE64.encode([9u8; 8])).is_err());
+    }
+}
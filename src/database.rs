@@ -1,255 +1,807 @@
-use sqlx::{PgPool, Row};
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use sqlx::{PgPool, Postgres, Row, Transaction};
 use uuid::Uuid;
-use chrono::Utc;
-use std::collections::HashMap;
-use crate::models::{User, CreateUser, UpdateUser, Port, CreatePort, H3HeatmapResponse, H3HeatmapCell};
-use crate::h3_utils::{coords_to_h3, calculate_distance_km, get_k_ring, DEFAULT_RESOLUTION};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use crate::models::{User, CreateUser, UpdateUser, Port, CreatePort, H3HeatmapResponse, HeatmapLayer, HeatmapMetric, HeatmapPoint, TimeRange, ApiKey, CreateApiKey, AuditAction, AuditEntity, AuditLogEntry, AuditLogQuery, LocationErasure, LocationPrecision, HealthStatus, MigrationCheck, H3Mismatch, SignupInterval, SignupCount, PortCount, PortStats};
+use crate::crypto::{EmailCipher, EncryptedEmail};
+use crate::heatmap;
+use crate::h3_audit::{check_record as check_h3_record, is_fixable, H3AuditBatch};
+use crate::h3_utils::{coords_to_h3, calculate_distance_km, get_k_ring, snap_to_cell_center, DEFAULT_RESOLUTION};
+use crate::validation::DEFAULT_ERASURE_RESOLUTION;
+
+/// Kişisel verileri silinen kullanıcıların adı
+pub const ERASED_USER_NAME: &str = "Silinmiş kullanıcı";
+
+/// Uygulamaya gömülü veritabanı migrasyonları
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Açılışta ve anahtar döndürmede tek seferde yeniden şifrelenen kullanıcı sayısı
+const REENCRYPT_BATCH_SIZE: i64 = 500;
 
 pub struct Database {
     pub pool: PgPool,
+    cipher: EmailCipher,
 }
 
 impl Database {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, cipher: EmailCipher) -> Self {
+        Self { pool, cipher }
+    }
+
+    pub fn cipher(&self) -> &EmailCipher {
+        &self.cipher
+    }
+
+    // Email'i etkin anahtarla şifrele
+    fn encrypt_email(&self, email: &str) -> Result<EncryptedEmail, sqlx::Error> {
+        self.cipher.encrypt(email).map_err(sqlx::Error::Protocol)
+    }
+
+    // Veritabanından okunan kullanıcının email'ini çöz
+    fn decrypt_user(&self, mut user: User) -> Result<User, sqlx::Error> {
+        user.email = self
+            .cipher
+            .decrypt(&user.email_key_id, &user.email_ciphertext, &user.email_hash)
+            .map_err(sqlx::Error::Protocol)?;
+        Ok(user)
+    }
+
+    fn decrypt_users(&self, users: Vec<User>) -> Result<Vec<User>, sqlx::Error> {
+        users.into_iter().map(|user| self.decrypt_user(user)).collect()
     }
 
-    // Tüm kullanıcıları getir
-    pub async fn get_all_users(&self) -> Result<Vec<User>, sqlx::Error> {
+    // ====== HEALTH CHECKS ======
+
+    // Veritabanına basit bir sorgu gönder
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT"))]
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    // Bağlantı havuzunun durumu: (açık, boşta, en fazla)
+    pub fn pool_usage(&self) -> (u32, u32, u32) {
+        (
+            self.pool.size(),
+            self.pool.num_idle() as u32,
+            self.pool.options().get_max_connections(),
+        )
+    }
+
+    // Gömülü migrasyonları veritabanında uygulanmış olanlarla karşılaştır
+    pub async fn migration_status(&self) -> Result<MigrationCheck, sqlx::Error> {
+        migration_status(&self.pool).await
+    }
+
+    // ====== H3 AUDIT ======
+
+    // H3 denetimi kapsamındaki kayıt sayısı (silinmişler dahil)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = entity.table()))]
+    pub async fn count_h3_records(&self, entity: AuditEntity) -> Result<u64, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", entity.table()))
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count as u64)
+    }
+
+    // `after` kimliğinden sonraki en fazla `limit` kaydın h3_index'ini koordinatlarıyla karşılaştır;
+    // `fix` ise düzeltilebilir uyuşmazlıklar aynı transaction'da yeniden hesaplanan index'le güncellenir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = entity.table(), h3.resolution = DEFAULT_RESOLUTION))]
+    pub async fn audit_h3_batch(&self, entity: AuditEntity, after: Option<Uuid>, limit: i64, fix: bool) -> Result<H3AuditBatch, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT id, latitude, longitude, h3_index FROM {} WHERE $1::uuid IS NULL OR id > $1 ORDER BY id LIMIT $2{}",
+            entity.table(),
+            if fix { " FOR UPDATE" } else { "" }
+        ))
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut mismatches: Vec<H3Mismatch> = rows
+            .iter()
+            .filter_map(|row| {
+                check_h3_record(entity, row.get("id"), row.get("latitude"), row.get("longitude"), row.get("h3_index"))
+            })
+            .collect();
+
+        if fix {
+            let fixable: Vec<&mut H3Mismatch> = mismatches.iter_mut().filter(|m| is_fixable(m)).collect();
+            if !fixable.is_empty() {
+                let ids: Vec<Uuid> = fixable.iter().map(|m| m.id).collect();
+                let indexes: Vec<Option<String>> = fixable.iter().map(|m| m.expected.clone()).collect();
+                sqlx::query(&format!(
+                    "UPDATE {} AS t SET h3_index = data.h3_index FROM UNNEST($1::uuid[], $2::text[]) AS data(id, h3_index) WHERE t.id = data.id",
+                    entity.table()
+                ))
+                .bind(&ids)
+                .bind(&indexes)
+                .execute(&mut *tx)
+                .await?;
+                for mismatch in fixable {
+                    mismatch.fixed = true;
+                }
+            }
+        }
+        tx.commit().await?;
+
+        Ok(H3AuditBatch {
+            last_id: rows.last().map(|row| row.get("id")),
+            checked: rows.len() as u64,
+            mismatches,
+        })
+    }
+
+    // ====== FIXTURES ======
+
+    // Yüklenmiş örnek veri setlerinin sürümleri
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "fixture_sets"))]
+    pub async fn get_fixture_versions(&self) -> Result<HashMap<String, i32>, sqlx::Error> {
+        let rows = sqlx::query("SELECT name, version FROM fixture_sets")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get("name"), row.get("version"))).collect())
+    }
+
+    // Örnek veri setinin yüklendiği sürümü kaydet
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "INSERT", db.sql.table = "fixture_sets"))]
+    pub async fn record_fixture_set(&self, name: &str, version: i32) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO fixture_sets (name, version, loaded_at) VALUES ($1, $2, NOW()) ON CONFLICT (name) DO UPDATE SET version = EXCLUDED.version, loaded_at = EXCLUDED.loaded_at"
+        )
+        .bind(name)
+        .bind(version)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ====== EMAIL ENCRYPTION ======
+
+    // Kullanıcı email'lerinin anahtarlara göre dağılımı: (anahtar kimliği, düz metin mi, kullanıcı sayısı)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn email_key_usage(&self) -> Result<Vec<(Option<String>, bool, i64)>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT email_key_id, email IS NOT NULL AS plaintext, COUNT(*) AS count FROM users GROUP BY 1, 2 ORDER BY 1, 2"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("email_key_id"), row.get("plaintext"), row.get("count")))
+            .collect())
+    }
+
+    // Düz metin, eski bir anahtarla veya AAD'siz şifrelenmiş email'leri etkin anahtarla yeniden şifrele; işlenen kullanıcı sayısı
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "users"))]
+    pub async fn reencrypt_emails(&self) -> Result<u64, sqlx::Error> {
+        let active_key_id = self.cipher.active_key_id();
+        let mut total = 0u64;
+
+        loop {
+            let mut tx = self.pool.begin().await?;
+            let rows = sqlx::query(
+                "SELECT id, email, email_ciphertext, email_key_id, email_hash, email_aad_bound FROM users WHERE email IS NOT NULL OR email_key_id <> $1 OR NOT email_aad_bound ORDER BY id LIMIT $2 FOR UPDATE SKIP LOCKED"
+            )
+            .bind(active_key_id)
+            .bind(REENCRYPT_BATCH_SIZE)
+            .fetch_all(&mut *tx)
+            .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let mut ids = Vec::with_capacity(rows.len());
+            let mut ciphertexts = Vec::with_capacity(rows.len());
+            let mut hashes = Vec::with_capacity(rows.len());
+            for row in &rows {
+                let plaintext: Option<String> = row.get("email");
+                let email = match plaintext {
+                    Some(email) => email,
+                    None => {
+                        let key_id: String = row.get("email_key_id");
+                        let ciphertext: Vec<u8> = row.get("email_ciphertext");
+                        let decrypted = if row.get("email_aad_bound") {
+                            self.cipher.decrypt(&key_id, &ciphertext, row.get("email_hash"))
+                        } else {
+                            self.cipher.decrypt_unbound(&key_id, &ciphertext)
+                        };
+                        decrypted.map_err(sqlx::Error::Protocol)?
+                    }
+                };
+                let encrypted = self.encrypt_email(&email)?;
+                ids.push(row.get::<Uuid, _>("id"));
+                ciphertexts.push(encrypted.ciphertext);
+                hashes.push(encrypted.hash);
+            }
+
+            sqlx::query(
+                "UPDATE users u SET email = NULL, email_ciphertext = t.email_ciphertext, email_key_id = $4, email_hash = t.email_hash, email_aad_bound = TRUE FROM UNNEST($1::UUID[], $2::BYTEA[], $3::TEXT[]) AS t(id, email_ciphertext, email_hash) WHERE u.id = t.id"
+            )
+            .bind(&ids)
+            .bind(&ciphertexts)
+            .bind(&hashes)
+            .bind(active_key_id)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            total += ids.len() as u64;
+        }
+
+        Ok(total)
+    }
+
+    // Şifreleme öncesi yazılmış denetim kayıtlarındaki düz metin email'leri kör indeksle değiştir; güncellenen kayıt sayısı
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "audit_log"))]
+    pub async fn hash_audit_log_emails(&self) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query(
+            "SELECT id, before->>'email' AS before_email, after->>'email' AS after_email FROM audit_log WHERE entity_type = $1 AND (before ? 'email' OR after ? 'email')"
+        )
+        .bind(AuditEntity::User.as_str())
+        .fetch_all(&mut *tx)
+        .await?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.get("id")).collect();
+        let hash = |column: &str| -> Vec<Option<String>> {
+            rows.iter()
+                .map(|row| row.get::<Option<String>, _>(column).map(|email| self.cipher.blind_index(&email)))
+                .collect()
+        };
+        let (before_hashes, after_hashes) = (hash("before_email"), hash("after_email"));
+
+        sqlx::query("SET LOCAL rustmicro.audit_scrub = 'on'")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE audit_log a SET before = CASE WHEN a.before ? 'email' THEN (a.before - 'email') || jsonb_build_object('email_hash', t.before_hash) ELSE a.before END, after = CASE WHEN a.after ? 'email' THEN (a.after - 'email') || jsonb_build_object('email_hash', t.after_hash) ELSE a.after END FROM UNNEST($1::UUID[], $2::TEXT[], $3::TEXT[]) AS t(id, before_hash, after_hash) WHERE a.id = t.id"
+        )
+        .bind(&ids)
+        .bind(&before_hashes)
+        .bind(&after_hashes)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("SET LOCAL rustmicro.audit_scrub = 'off'")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(ids.len() as u64)
+    }
+
+    // Kullanıcının güncel hassasiyetinden daha hassas konum içeren denetim kayıtlarını indirge; güncellenen kayıt sayısı
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "audit_log"))]
+    pub async fn coarsen_audit_log_locations(&self) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let count = coarsen_audit_locations(&mut tx, None).await?;
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    // Tüm kullanıcıları getir (include_deleted ile silinmişler de dahil edilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn get_all_users(&self, include_deleted: bool) -> Result<Vec<User>, sqlx::Error> {
         let users = sqlx::query_as::<_, User>(
-            "SELECT id, name, email, latitude, longitude, h3_index, created_at, updated_at FROM users ORDER BY created_at DESC"
+            "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE ($1 OR deleted_at IS NULL) ORDER BY created_at DESC"
         )
+        .bind(include_deleted)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(users)
+        self.decrypt_users(users)
     }
 
-    // ID'ye göre kullanıcı getir
-    pub async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>, sqlx::Error> {
+    // ID'ye göre kullanıcı getir (include_deleted ile silinmişler de dahil edilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn get_user_by_id(&self, id: Uuid, include_deleted: bool) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
-            "SELECT id, name, email, latitude, longitude, h3_index, created_at, updated_at FROM users WHERE id = $1"
+            "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE id = $1 AND ($2 OR deleted_at IS NULL)"
         )
         .bind(id)
+        .bind(include_deleted)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(user)
+        user.map(|user| self.decrypt_user(user)).transpose()
     }
 
-    // Email'e göre kullanıcı getir
+    // Email'e göre silinmemiş kullanıcıyı getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
     pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
-            "SELECT id, name, email, latitude, longitude, h3_index, created_at, updated_at FROM users WHERE email = $1"
+            "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE email_hash = $1 AND deleted_at IS NULL"
         )
-        .bind(email)
+        .bind(self.cipher.blind_index(email))
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(user)
+        user.map(|user| self.decrypt_user(user)).transpose()
     }
 
     // Yeni kullanıcı oluştur
-    pub async fn create_user(&self, user_data: CreateUser) -> Result<User, sqlx::Error> {
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "INSERT", db.sql.table = "users"))]
+    pub async fn create_user(&self, user_data: CreateUser, actor: &str) -> Result<User, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
         
-        // Eğer latitude ve longitude verilmişse H3 index hesapla
-        let h3_index = if let (Some(lat), Some(lng)) = (user_data.latitude, user_data.longitude) {
-            coords_to_h3(lat, lng, DEFAULT_RESOLUTION).ok()
-        } else {
-            None
-        };
+        // Konumu kullanıcının onay verdiği hassasiyete indirge ve H3 index hesapla
+        let precision = user_data.location_precision.unwrap_or_default();
+        let (latitude, longitude, h3_index) = apply_location_precision(user_data.latitude, user_data.longitude, precision)?;
+
+        let email = self.encrypt_email(&user_data.email)?;
 
+        let mut tx = self.pool.begin().await?;
         let user = sqlx::query_as::<_, User>(
-            "INSERT INTO users (id, name, email, latitude, longitude, h3_index, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id, name, email, latitude, longitude, h3_index, created_at, updated_at"
+            "INSERT INTO users (id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
         )
         .bind(id)
         .bind(&user_data.name)
-        .bind(&user_data.email)
-        .bind(user_data.latitude)
-        .bind(user_data.longitude)
+        .bind(&email.ciphertext)
+        .bind(&email.key_id)
+        .bind(&email.hash)
+        .bind(latitude)
+        .bind(longitude)
         .bind(h3_index)
+        .bind(precision)
         .bind(now)
         .bind(now)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(user)
+        record_audit(&mut tx, actor, AuditAction::Create, AuditEntity::User, user.id, None, Some(&user)).await?;
+        tx.commit().await?;
+
+        self.decrypt_user(user)
     }
 
-    // Kullanıcı güncelle
-    pub async fn update_user(&self, id: Uuid, user_data: UpdateUser) -> Result<Option<User>, sqlx::Error> {
+    // Kullanıcı güncelle (verilmeyen alanlar olduğu gibi kalır)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "users"))]
+    pub async fn update_user(&self, id: Uuid, user_data: UpdateUser, actor: &str) -> Result<Option<User>, sqlx::Error> {
+        if user_data.name.is_none()
+            && user_data.email.is_none()
+            && user_data.latitude.is_none()
+            && user_data.longitude.is_none()
+            && user_data.location_precision.is_none()
+        {
+            return self.get_user_by_id(id, false).await;
+        }
+
         let now = Utc::now();
 
-        // Basit bir update işlemi yapalım
-        let user = if let Some(name) = user_data.name {
-            if let Some(email) = user_data.email {
-                sqlx::query_as::<_, User>(
-                    "UPDATE users SET name = $1, email = $2, updated_at = $3 WHERE id = $4 RETURNING id, name, email, created_at, updated_at"
-                )
-                .bind(name)
-                .bind(email)
-                .bind(now)
-                .bind(id)
-                .fetch_optional(&self.pool)
-                .await?
-            } else {
-                sqlx::query_as::<_, User>(
-                    "UPDATE users SET name = $1, updated_at = $2 WHERE id = $3 RETURNING id, name, email, created_at, updated_at"
-                )
-                .bind(name)
-                .bind(now)
-                .bind(id)
-                .fetch_optional(&self.pool)
-                .await?
+        let email = user_data.email.as_deref().map(|email| self.encrypt_email(email)).transpose()?;
+
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = lock_user(&mut tx, id, false).await? else {
+            return Ok(None);
+        };
+
+        // Konum veya hassasiyet değiştiyse konumu (yeni) hassasiyete indirge ve H3 index'i yeniden hesapla
+        let precision = user_data.location_precision.unwrap_or(before.location_precision);
+        let (latitude, longitude, h3_index) = match (user_data.latitude, user_data.longitude) {
+            (Some(lat), Some(lng)) => apply_location_precision(Some(lat), Some(lng), precision)?,
+            _ if precision != before.location_precision => {
+                apply_location_precision(before.latitude, before.longitude, precision)?
             }
-        } else if let Some(email) = user_data.email {
-            sqlx::query_as::<_, User>(
-                "UPDATE users SET email = $1, updated_at = $2 WHERE id = $3 RETURNING id, name, email, created_at, updated_at"
-            )
-            .bind(email)
-            .bind(now)
-            .bind(id)
-            .fetch_optional(&self.pool)
-            .await?
-        } else {
-            return self.get_user_by_id(id).await;
+            _ => (before.latitude, before.longitude, before.h3_index.clone()),
         };
 
-        Ok(user)
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET name = COALESCE($1, name), email_ciphertext = COALESCE($2, email_ciphertext), email_key_id = COALESCE($3, email_key_id), email_hash = COALESCE($4, email_hash), latitude = $5, longitude = $6, h3_index = $7, location_precision = $8, updated_at = $9 WHERE id = $10 RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(user_data.name)
+        .bind(email.as_ref().map(|email| &email.ciphertext))
+        .bind(email.as_ref().map(|email| &email.key_id))
+        .bind(email.as_ref().map(|email| &email.hash))
+        .bind(latitude)
+        .bind(longitude)
+        .bind(h3_index)
+        .bind(precision)
+        .bind(now)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Hassasiyet kabalaştıysa geçmiş denetim kayıtlarındaki konumlar da yeni hassasiyete indirgenir
+        if precision > before.location_precision {
+            coarsen_audit_locations(&mut tx, Some(id)).await?;
+        }
+        let before = with_location_precision(before, precision)?;
+        record_audit(&mut tx, actor, AuditAction::Update, AuditEntity::User, id, Some(&before), Some(&user)).await?;
+        tx.commit().await?;
+
+        self.decrypt_user(user).map(Some)
     }
 
-    // Kullanıcı sil
-    pub async fn delete_user(&self, id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM users WHERE id = $1")
-            .bind(id)
-            .execute(&self.pool)
+    // Kullanıcıyı sil (soft delete; saklama süresi dolana kadar geri yüklenebilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "users"))]
+    pub async fn delete_user(&self, id: Uuid, actor: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = lock_user(&mut tx, id, false).await? else {
+            return Ok(false);
+        };
+
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET deleted_at = $1 WHERE id = $2 RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_audit(&mut tx, actor, AuditAction::Delete, AuditEntity::User, id, Some(&before), Some(&user)).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    // Silinmiş kullanıcıyı geri yükle
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "users"))]
+    pub async fn restore_user(&self, id: Uuid, actor: &str) -> Result<Option<User>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = lock_user(&mut tx, id, true).await? else {
+            return Ok(None);
+        };
+
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET deleted_at = NULL, updated_at = $1 WHERE id = $2 RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_audit(&mut tx, actor, AuditAction::Restore, AuditEntity::User, id, Some(&before), Some(&user)).await?;
+        tx.commit().await?;
+
+        self.decrypt_user(user).map(Some)
+    }
+
+    // Kullanıcının kişisel verilerini sil (GDPR); kayıt, kaba konumu ile aggregate'ler için kalır.
+    // Denetim kayıtlarındaki ad, email ve konum da silinmiş değerlerle değiştirilir.
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "users", h3.resolution = resolution))]
+    pub async fn erase_user(&self, id: Uuid, location: LocationErasure, resolution: u8, actor: &str) -> Result<Option<User>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = sqlx::query_as::<_, User>(
+            "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE id = $1 FOR UPDATE"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let (latitude, longitude, h3_index) = match (location, before.latitude, before.longitude) {
+            (LocationErasure::Coarsen, Some(lat), Some(lng)) => {
+                let (center_lat, center_lng, _) = snap_to_cell_center(lat, lng, resolution)
+                    .map_err(|e| sqlx::Error::Protocol(format!("H3 hesaplama hatası: {}", e)))?;
+                // Saklanan indeks, diğer kayıtlarla tutarlı olması için merkezin varsayılan çözünürlükteki hücresidir
+                let h3_index = coords_to_h3(center_lat, center_lng, DEFAULT_RESOLUTION).ok();
+                (Some(center_lat), Some(center_lng), h3_index)
+            }
+            _ => (None, None, None),
+        };
+        // Konumu tamamen silinen kullanıcı için hassasiyet `none` olur; sonraki güncellemeler konum saklamaz
+        let precision = match location {
+            LocationErasure::Delete => LocationPrecision::None,
+            LocationErasure::Coarsen => before.location_precision,
+        };
+
+        let email = self.encrypt_email(&format!("erased-{}@erased.invalid", id))?;
+
+        let now = Utc::now();
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET name = $1, email_ciphertext = $2, email_key_id = $3, email_hash = $4, latitude = $5, longitude = $6, h3_index = $7, location_precision = $8, erased_at = $9, updated_at = $9 WHERE id = $10 RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(ERASED_USER_NAME)
+        .bind(&email.ciphertext)
+        .bind(&email.key_id)
+        .bind(&email.hash)
+        .bind(latitude)
+        .bind(longitude)
+        .bind(h3_index)
+        .bind(precision)
+        .bind(now)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Geçmiş denetim kayıtlarındaki kişisel verileri silinmiş değerlerle değiştir
+        let redacted = serde_json::json!({
+            "name": user.name,
+            "email_hash": user.email_hash,
+            "latitude": user.latitude,
+            "longitude": user.longitude,
+            "h3_index": user.h3_index,
+        });
+        sqlx::query("SET LOCAL rustmicro.audit_scrub = 'on'")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE audit_log SET before = CASE WHEN before IS NULL THEN NULL ELSE (before - 'email') || $1 END, after = CASE WHEN after IS NULL THEN NULL ELSE (after - 'email') || $1 END WHERE entity_type = $2 AND entity_id = $3"
+        )
+        .bind(&redacted)
+        .bind(AuditEntity::User.as_str())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("SET LOCAL rustmicro.audit_scrub = 'off'")
+            .execute(&mut *tx)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        record_audit::<User>(&mut tx, actor, AuditAction::Erase, AuditEntity::User, id, None, Some(&user)).await?;
+        tx.commit().await?;
+
+        self.decrypt_user(user).map(Some)
+    }
+
+    // ====== BULK USER OPERATIONS ======
+
+    // Verilen email'lerden silinmemiş bir kullanıcıda kullanılanları getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn existing_user_emails(&self, emails: &[String]) -> Result<HashSet<String>, sqlx::Error> {
+        // Email'ler kör indeksleri üzerinden aranır
+        let by_hash: HashMap<String, &String> = emails.iter().map(|email| (self.cipher.blind_index(email), email)).collect();
+        let hashes: Vec<String> = by_hash.keys().cloned().collect();
+        let rows = sqlx::query("SELECT email_hash FROM users WHERE email_hash = ANY($1) AND deleted_at IS NULL")
+            .bind(&hashes)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| by_hash.get(row.get::<&str, _>("email_hash")).map(|email| (*email).clone()))
+            .collect())
+    }
+
+    // Kullanıcıları tek sorguda toplu ekle; email'i bu arada alınmış satırlar atlanır ve dönmez
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "INSERT", db.sql.table = "users"))]
+    pub async fn import_users(&self, users: Vec<CreateUser>, actor: &str) -> Result<Vec<User>, sqlx::Error> {
+        if users.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let now = Utc::now();
+        let mut ids = Vec::with_capacity(users.len());
+        let mut names = Vec::with_capacity(users.len());
+        let mut ciphertexts = Vec::with_capacity(users.len());
+        let mut key_ids = Vec::with_capacity(users.len());
+        let mut hashes = Vec::with_capacity(users.len());
+        let mut latitudes = Vec::with_capacity(users.len());
+        let mut longitudes = Vec::with_capacity(users.len());
+        let mut h3_indices = Vec::with_capacity(users.len());
+        let mut precisions = Vec::with_capacity(users.len());
+        for user in users {
+            // Email şifrelemesi, konum hassasiyeti ve H3 index yükleme sırasında uygulanır
+            let precision = user.location_precision.unwrap_or_default();
+            let (latitude, longitude, h3_index) = apply_location_precision(user.latitude, user.longitude, precision)?;
+            let email = self.encrypt_email(&user.email)?;
+            ids.push(Uuid::new_v4());
+            names.push(user.name);
+            ciphertexts.push(email.ciphertext);
+            key_ids.push(email.key_id);
+            hashes.push(email.hash);
+            latitudes.push(latitude);
+            longitudes.push(longitude);
+            h3_indices.push(h3_index);
+            precisions.push(precision.as_str());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query_as::<_, User>(
+            "INSERT INTO users (id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at) SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, $10, $10 FROM UNNEST($1::UUID[], $2::VARCHAR[], $3::BYTEA[], $4::TEXT[], $5::TEXT[], $6::DOUBLE PRECISION[], $7::DOUBLE PRECISION[], $8::VARCHAR[], $9::TEXT[]) AS t(id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision) ON CONFLICT (email_hash) WHERE deleted_at IS NULL DO NOTHING RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(&ids)
+        .bind(&names)
+        .bind(&ciphertexts)
+        .bind(&key_ids)
+        .bind(&hashes)
+        .bind(&latitudes)
+        .bind(&longitudes)
+        .bind(&h3_indices)
+        .bind(&precisions)
+        .bind(now)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let entity_ids: Vec<Uuid> = inserted.iter().map(|user| user.id).collect();
+        let afters = inserted
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| sqlx::Error::Protocol(format!("Denetim kaydı serileştirilemedi: {}", e)))?;
+        sqlx::query(
+            "INSERT INTO audit_log (id, occurred_at, actor, action, entity_type, entity_id, after) SELECT uuid_generate_v4(), $1, $2, $3, $4, entity_id, after FROM UNNEST($5::UUID[], $6::JSONB[]) AS t(entity_id, after)"
+        )
+        .bind(now)
+        .bind(actor)
+        .bind(AuditAction::Create.as_str())
+        .bind(AuditEntity::User.as_str())
+        .bind(&entity_ids)
+        .bind(&afters)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.decrypt_users(inserted)
+    }
+
+    // Kullanıcıları id sırasıyla sayfa sayfa getir (dışa aktarma için keyset sayfalama)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn get_users_page(&self, after_id: Option<Uuid>, limit: i64, include_deleted: bool) -> Result<Vec<User>, sqlx::Error> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE ($1::UUID IS NULL OR id > $1) AND ($2 OR deleted_at IS NULL) ORDER BY id LIMIT $3"
+        )
+        .bind(after_id)
+        .bind(include_deleted)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.decrypt_users(users)
     }
 
     // Kullanıcı sayısını getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
     pub async fn count_users(&self) -> Result<i64, sqlx::Error> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM users")
+        let row = sqlx::query("SELECT COUNT(*) as count FROM users WHERE deleted_at IS NULL")
             .fetch_one(&self.pool)
             .await?;
 
         let count: i64 = row.get("count");
         Ok(count)
     }
-    
-    // H3 index'e göre kullanıcıları getir
-    pub async fn get_users_by_h3(&self, h3_index: &str) -> Result<Vec<User>, sqlx::Error> {
-        let users = sqlx::query_as::<_, User>(
-            "SELECT id, name, email, latitude, longitude, h3_index, created_at, updated_at FROM users WHERE h3_index = $1"
+
+    // Konumu olan ve olmayan kullanıcı sayılarını getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn count_users_by_location(&self) -> Result<(i64, i64), sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) FILTER (WHERE latitude IS NOT NULL AND longitude IS NOT NULL) AS with_location, COUNT(*) FILTER (WHERE latitude IS NULL OR longitude IS NULL) AS without_location FROM users WHERE deleted_at IS NULL"
         )
-        .bind(h3_index)
-        .fetch_all(&self.pool)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(users)
+        Ok((row.get("with_location"), row.get("without_location")))
     }
-    
-    // Belirli bir alan içindeki kullanıcıları getir (H3 index listesi ile)
-    pub async fn get_users_in_area(&self, h3_indices: Vec<String>) -> Result<Vec<User>, sqlx::Error> {
-        if h3_indices.is_empty() {
-            return Ok(vec![]);
-        }
-        
-        let users = sqlx::query_as::<_, User>(
-            "SELECT id, name, email, latitude, longitude, h3_index, created_at, updated_at FROM users WHERE h3_index = ANY($1)"
+
+    // Son `periods` aralıkta kaydolan kullanıcı sayılarını getir (UTC, boş aralıklar sıfır olarak)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users", stats.interval = interval.as_str()))]
+    pub async fn get_signup_counts(&self, interval: SignupInterval, periods: i64) -> Result<Vec<SignupCount>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            WITH bounds AS (
+                SELECT date_trunc($1, now() AT TIME ZONE 'UTC') AS last, ('1 ' || $1)::interval AS step
+            )
+            SELECT p.period AT TIME ZONE 'UTC' AS period_start, COUNT(u.id) AS count
+            FROM bounds b
+            CROSS JOIN generate_series(b.last - b.step * ($2 - 1), b.last, b.step) AS p(period)
+            LEFT JOIN users u
+                ON u.deleted_at IS NULL
+               AND u.created_at AT TIME ZONE 'UTC' >= p.period
+               AND u.created_at AT TIME ZONE 'UTC' < p.period + b.step
+            GROUP BY p.period
+            ORDER BY p.period
+            "#
         )
-        .bind(&h3_indices)
+        .bind(interval.as_str())
+        .bind(periods)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(users)
+        Ok(rows
+            .into_iter()
+            .map(|row| SignupCount {
+                period_start: row.get("period_start"),
+                count: row.get("count"),
+            })
+            .collect())
     }
     
-    // H3 tabanlı kullanıcı heatmap verilerini getir (dinamik resolution ile)
-    pub async fn get_user_heatmap_data(&self, resolution: u8) -> Result<H3HeatmapResponse, sqlx::Error> {
-        // Kullanıcıları al ve belirtilen resolution'a göre H3 indekslerini yeniden hesapla
-        let users = sqlx::query(
-            "SELECT latitude, longitude FROM users WHERE latitude IS NOT NULL AND longitude IS NOT NULL"
+    // Konumu olan ve verilen aralıkta oluşturulan kullanıcıların koordinatlarını getir (enlem, boylam)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "users"))]
+    pub async fn get_user_locations(&self, created: TimeRange) -> Result<Vec<(f64, f64)>, sqlx::Error> {
+        sqlx::query_as::<_, (f64, f64)>(
+            "SELECT latitude, longitude FROM users WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND deleted_at IS NULL AND ($1::TIMESTAMPTZ IS NULL OR created_at >= $1) AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2)"
         )
+        .bind(created.from)
+        .bind(created.to)
         .fetch_all(&self.pool)
-        .await?;
+        .await
+    }
 
-        // H3 indekslerini yeni resolution ile hesapla ve grupla
-        let mut h3_groups: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
-        
-        for user in users {
-            let lat: f64 = user.get("latitude");
-            let lng: f64 = user.get("longitude");
-            
-            // Yeni resolution ile H3 indeksini hesapla
-            if let Ok(h3_index) = coords_to_h3(lat, lng, resolution) {
-                h3_groups.entry(h3_index).or_insert_with(Vec::new).push((lat, lng));
-            }
-        }
+    // H3 tabanlı kullanıcı heatmap verilerini getir (dinamik resolution ile)
+    pub async fn get_user_heatmap_data(&self, resolution: u8) -> Result<H3HeatmapResponse, sqlx::Error> {
+        self.get_heatmap_data(HeatmapLayer::Users, HeatmapMetric::Count, None, resolution, TimeRange::default()).await
+    }
 
-        // Grupları H3HeatmapCell'lere dönüştür
-        let mut cells = Vec::new();
+    // Bir katmanın `created` aralığında oluşturulan kayıtlarının heatmap verilerini getir; `field` katmanın
+    // `value_fields` listesinden olmalıdır
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = layer.as_str(), h3.resolution = resolution))]
+    pub async fn get_heatmap_data(
+        &self,
+        layer: HeatmapLayer,
+        metric: HeatmapMetric,
+        field: Option<&'static str>,
+        resolution: u8,
+        created: TimeRange,
+    ) -> Result<H3HeatmapResponse, sqlx::Error> {
+        let points = match layer {
+            HeatmapLayer::Users => self
+                .get_user_locations(created)
+                .await?
+                .into_iter()
+                .map(|(latitude, longitude)| HeatmapPoint { latitude, longitude, value: None })
+                .collect(),
+            HeatmapLayer::Ports => self.get_port_heatmap_points(field, created).await?,
+        };
 
-        let mut total_users = 0i64;
-        
-        for (h3_index, coords) in h3_groups {
-            let user_count = coords.len() as i64;
-            total_users += user_count;
-            
-            // Koordinatların ortalamasını hesapla (hücre merkezi)
-            let center_lat = coords.iter().map(|(lat, _)| lat).sum::<f64>() / coords.len() as f64;
-            let center_lng = coords.iter().map(|(_, lng)| lng).sum::<f64>() / coords.len() as f64;
-            
-            cells.push(H3HeatmapCell {
-                h3_index,
-                user_count,
-                center_latitude: center_lat,
-                center_longitude: center_lng,
-            });
+        let mut response = heatmap::aggregate(layer, metric, field.map(str::to_string), &points, resolution);
+        if created.from.is_some() || created.to.is_some() {
+            response.window = Some(created);
         }
-        
-        // Kullanıcı sayısına göre sırala
-        cells.sort_by(|a, b| b.user_count.cmp(&a.user_count));
+        Ok(response)
+    }
 
-        Ok(H3HeatmapResponse {
-            cells,
-            total_users,
-            resolution,
-        })
+    // Verilen aralıkta oluşturulan limanların konumlarını ve (verilirse) sayısal bir alanını getir
+    async fn get_port_heatmap_points(&self, field: Option<&'static str>, created: TimeRange) -> Result<Vec<HeatmapPoint>, sqlx::Error> {
+        let column = field.unwrap_or("NULL");
+        let rows = sqlx::query_as::<_, (f64, f64, Option<f64>)>(&format!(
+            "SELECT latitude, longitude, {column}::FLOAT8 FROM ports WHERE deleted_at IS NULL AND ($1::TIMESTAMPTZ IS NULL OR created_at >= $1) AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2)"
+        ))
+        .bind(created.from)
+        .bind(created.to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(latitude, longitude, value)| HeatmapPoint { latitude, longitude, value })
+            .collect())
     }
     
     // ====== PORT OPERATIONS ====== 
     
-    // Tüm limanları getir
-    pub async fn get_all_ports(&self) -> Result<Vec<Port>, sqlx::Error> {
+    // Tüm limanları getir (include_deleted ile silinmişler de dahil edilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
+    pub async fn get_all_ports(&self, include_deleted: bool) -> Result<Vec<Port>, sqlx::Error> {
         let ports = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports ORDER BY name"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE ($1 OR deleted_at IS NULL) ORDER BY name"
         )
+        .bind(include_deleted)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(ports)
     }
     
-    // ID'ye göre liman getir
-    pub async fn get_port_by_id(&self, id: Uuid) -> Result<Option<Port>, sqlx::Error> {
+    // ID'ye göre liman getir (include_deleted ile silinmişler de dahil edilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
+    pub async fn get_port_by_id(&self, id: Uuid, include_deleted: bool) -> Result<Option<Port>, sqlx::Error> {
         let port = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports WHERE id = $1"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE id = $1 AND ($2 OR deleted_at IS NULL)"
         )
         .bind(id)
+        .bind(include_deleted)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(port)
     }
     
-    // Koda göre liman getir
+    // Koda göre silinmemiş limanı getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
     pub async fn get_port_by_code(&self, code: &str) -> Result<Option<Port>, sqlx::Error> {
         let port = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports WHERE code = $1"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE code = $1 AND deleted_at IS NULL"
         )
         .bind(code)
         .fetch_optional(&self.pool)
@@ -259,7 +811,8 @@ impl Database {
     }
     
     // Yeni liman oluştur
-    pub async fn create_port(&self, port_data: CreatePort) -> Result<Port, sqlx::Error> {
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "INSERT", db.sql.table = "ports"))]
+    pub async fn create_port(&self, port_data: CreatePort, actor: &str) -> Result<Port, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
         
@@ -267,8 +820,9 @@ impl Database {
         let h3_index = coords_to_h3(port_data.latitude, port_data.longitude, DEFAULT_RESOLUTION)
             .map_err(|e| sqlx::Error::Protocol(format!("H3 hesaplama hatası: {}", e)))?;
 
+        let mut tx = self.pool.begin().await?;
         let port = sqlx::query_as::<_, Port>(
-            "INSERT INTO ports (id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at"
+            "INSERT INTO ports (id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at"
         )
         .bind(id)
         .bind(&port_data.name)
@@ -282,55 +836,60 @@ impl Database {
         .bind(port_data.capacity)
         .bind(now)
         .bind(now)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        record_audit(&mut tx, actor, AuditAction::Create, AuditEntity::Port, port.id, None, Some(&port)).await?;
+        tx.commit().await?;
+
         Ok(port)
     }
     
     // En yakın limanı bul (gerçek km mesafesi ile)
-    pub async fn find_nearest_port(&self, lat: f64, lng: f64, max_distance_km: Option<f64>) -> Result<Vec<Port>, sqlx::Error> {
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports", h3.index = tracing::field::Empty, h3.resolution = DEFAULT_RESOLUTION, h3.k_ring = tracing::field::Empty))]
+    pub async fn find_nearest_port(&self, lat: f64, lng: f64, max_distance_km: f64, k: u32) -> Result<Vec<Port>, sqlx::Error> {
         // H3 index hesapla
         let target_h3 = coords_to_h3(lat, lng, DEFAULT_RESOLUTION)
             .map_err(|e| sqlx::Error::Protocol(format!("H3 hesaplama hatası: {}", e)))?;
-        println!("Target H3: {}", target_h3);
 
-        // Varsayılan max mesafe 50 km, makul k değeri: 20 (yaklaşık 3.5 km yarıçapında arama)
-        let max_dist = max_distance_km.unwrap_or(50.0);
-        let k = 20; // Sabit makul değer
-        println!("Max distance: {} km, k: {}", max_dist, k);
+        tracing::Span::current()
+            .record("h3.index", target_h3.as_str())
+            .record("h3.k_ring", k);
 
         // kRing ile çevredeki hücreler
         let ring_indices = get_k_ring(&target_h3, k)
             .map_err(|e| sqlx::Error::Protocol(format!("kRing hesaplama hatası: {}", e)))?;
-        println!("Ring indices count: {}", ring_indices.len());
 
         if ring_indices.is_empty() {
-            println!("Ring indices boş!");
             return Ok(vec![]);
         }
 
         // Ring içindeki hücrelerdeki limanları çek
         let ports = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports WHERE h3_index = ANY($1)"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE h3_index = ANY($1) AND deleted_at IS NULL"
         )
         .bind(&ring_indices)
         .fetch_all(&self.pool)
         .await?;
-        println!("Found {} ports in ring", ports.len());
 
         // Limanları gerçek mesafeye göre filtrele ve en yakın limanı bul
         let mut nearest_ports: Vec<(Port, f64)> = vec![];
         for port in ports {
             let distance = calculate_distance_km(lat, lng, port.latitude, port.longitude);
-            println!("Port: {} - Distance: {:.2} km (max: {:.2} km)", port.name, distance, max_dist);
-            if distance <= max_dist {
-                let port_name = port.name.clone();
+            if distance <= max_distance_km {
                 nearest_ports.push((port, distance));
-                println!("Added port: {} at {:.2} km", port_name, distance);
             }
         }
 
+        tracing::debug!(
+            %target_h3,
+            k,
+            max_distance_km,
+            candidates = ring_indices.len(),
+            matched = nearest_ports.len(),
+            "En yakın liman araması"
+        );
+
         // Mesafeye göre sırala
         nearest_ports.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 
@@ -340,9 +899,10 @@ impl Database {
     }
     
     // Ülkeye göre limanları getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
     pub async fn get_ports_by_country(&self, country: &str) -> Result<Vec<Port>, sqlx::Error> {
         let ports = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports WHERE country ILIKE $1 ORDER BY name"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE country ILIKE $1 AND deleted_at IS NULL ORDER BY name"
         )
         .bind(format!("%{}%", country))
         .fetch_all(&self.pool)
@@ -352,9 +912,10 @@ impl Database {
     }
     
     // Liman tipine göre limanları getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
     pub async fn get_ports_by_type(&self, port_type: &str) -> Result<Vec<Port>, sqlx::Error> {
         let ports = sqlx::query_as::<_, Port>(
-            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at FROM ports WHERE port_type = $1 ORDER BY capacity DESC NULLS LAST"
+            "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE port_type = $1 AND deleted_at IS NULL ORDER BY capacity DESC NULLS LAST"
         )
         .bind(port_type)
         .fetch_all(&self.pool)
@@ -363,13 +924,467 @@ impl Database {
         Ok(ports)
     }
     
+    // Limanı sil (soft delete; saklama süresi dolana kadar geri yüklenebilir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "ports"))]
+    pub async fn delete_port(&self, id: Uuid, actor: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = lock_port(&mut tx, id, false).await? else {
+            return Ok(false);
+        };
+
+        let port = sqlx::query_as::<_, Port>(
+            "UPDATE ports SET deleted_at = $1 WHERE id = $2 RETURNING id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at"
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_audit(&mut tx, actor, AuditAction::Delete, AuditEntity::Port, id, Some(&before), Some(&port)).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    // Silinmiş limanı geri yükle
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "ports"))]
+    pub async fn restore_port(&self, id: Uuid, actor: &str) -> Result<Option<Port>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(before) = lock_port(&mut tx, id, true).await? else {
+            return Ok(None);
+        };
+
+        let port = sqlx::query_as::<_, Port>(
+            "UPDATE ports SET deleted_at = NULL, updated_at = $1 WHERE id = $2 RETURNING id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at"
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_audit(&mut tx, actor, AuditAction::Restore, AuditEntity::Port, id, Some(&before), Some(&port)).await?;
+        tx.commit().await?;
+
+        Ok(Some(port))
+    }
+
+    // Saklama süresini aşmış silinmiş kayıtları kalıcı olarak sil: (kullanıcı, liman) sayısı.
+    // Denetim kaydına yalnızca kimlik, zaman damgaları ve silme çözünürlüğündeki H3 hücresi yazılır; kullanıcıların
+    // geçmiş denetim kayıtlarındaki ad, email ve konum da silinir.
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "DELETE", db.sql.table = "users, ports"))]
+    pub async fn purge_deleted(&self, older_than: DateTime<Utc>, actor: &str) -> Result<(u64, u64), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let users = sqlx::query_as::<_, User>(
+            "DELETE FROM users WHERE deleted_at IS NOT NULL AND deleted_at < $1 RETURNING id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at"
+        )
+        .bind(older_than)
+        .fetch_all(&mut *tx)
+        .await?;
+        if !users.is_empty() {
+            let ids: Vec<Uuid> = users.iter().map(|user| user.id).collect();
+            sqlx::query("SET LOCAL rustmicro.audit_scrub = 'on'")
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "UPDATE audit_log SET before = before - ARRAY['name', 'email', 'email_hash', 'latitude', 'longitude', 'h3_index'], after = after - ARRAY['name', 'email', 'email_hash', 'latitude', 'longitude', 'h3_index'] WHERE entity_type = $1 AND entity_id = ANY($2)"
+            )
+            .bind(AuditEntity::User.as_str())
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("SET LOCAL rustmicro.audit_scrub = 'off'")
+                .execute(&mut *tx)
+                .await?;
+        }
+        for user in &users {
+            let snapshot = PurgedRecord::new(user.id, user.latitude.zip(user.longitude), user.created_at, user.updated_at, user.deleted_at);
+            record_audit(&mut tx, actor, AuditAction::Purge, AuditEntity::User, user.id, Some(&snapshot), None).await?;
+        }
+
+        let ports = sqlx::query_as::<_, Port>(
+            "DELETE FROM ports WHERE deleted_at IS NOT NULL AND deleted_at < $1 RETURNING id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at"
+        )
+        .bind(older_than)
+        .fetch_all(&mut *tx)
+        .await?;
+        for port in &ports {
+            let snapshot = PurgedRecord::new(port.id, Some((port.latitude, port.longitude)), port.created_at, port.updated_at, port.deleted_at);
+            record_audit(&mut tx, actor, AuditAction::Purge, AuditEntity::Port, port.id, Some(&snapshot), None).await?;
+        }
+        tx.commit().await?;
+
+        Ok((users.len() as u64, ports.len() as u64))
+    }
+
     // Liman sayısını getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
     pub async fn count_ports(&self) -> Result<i64, sqlx::Error> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM ports")
+        let row = sqlx::query("SELECT COUNT(*) as count FROM ports WHERE deleted_at IS NULL")
             .fetch_one(&self.pool)
             .await?;
 
         let count: i64 = row.get("count");
         Ok(count)
     }
+
+    // Ülke, tip ve kapasiteye göre liman istatistiklerini getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "ports"))]
+    pub async fn get_port_stats(&self) -> Result<PortStats, sqlx::Error> {
+        let total = self.count_ports().await?;
+        let by_country = self.count_ports_by("country").await?;
+        let by_type = self.count_ports_by("port_type").await?;
+
+        let row = sqlx::query(
+            "SELECT COUNT(capacity) AS with_capacity, COALESCE(SUM(capacity), 0)::BIGINT AS total_capacity, AVG(capacity)::FLOAT8 AS average_capacity FROM ports WHERE deleted_at IS NULL"
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(PortStats {
+            total,
+            by_country,
+            by_type,
+            with_capacity: row.get("with_capacity"),
+            total_capacity_teu: row.get("total_capacity"),
+            average_capacity_teu: row.get("average_capacity"),
+        })
+    }
+
+    // Limanları bir sütuna göre gruplayıp sayar; `column` yalnızca sabit sütun adlarıyla çağrılır
+    async fn count_ports_by(&self, column: &'static str) -> Result<Vec<PortCount>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {column} AS key, COUNT(*) AS count FROM ports WHERE deleted_at IS NULL GROUP BY {column} ORDER BY count DESC, key"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PortCount { key: row.get("key"), count: row.get("count") })
+            .collect())
+    }
+
+    // ====== API KEY OPERATIONS ======
+
+    // Tüm API anahtarlarını getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "api_keys"))]
+    pub async fn get_all_api_keys(&self) -> Result<Vec<ApiKey>, sqlx::Error> {
+        let api_keys = sqlx::query_as::<_, ApiKey>(
+            "SELECT id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_at FROM api_keys ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(api_keys)
+    }
+
+    // Hash'e göre API anahtarı getir
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "api_keys"))]
+    pub async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, sqlx::Error> {
+        let api_key = sqlx::query_as::<_, ApiKey>(
+            "SELECT id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_at FROM api_keys WHERE key_hash = $1"
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    // Yeni API anahtarı kaydet (anahtarın kendisi değil, hash'i saklanır)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "INSERT", db.sql.table = "api_keys"))]
+    pub async fn create_api_key(&self, key_data: CreateApiKey, prefix: &str, key_hash: &str) -> Result<ApiKey, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes: Vec<String> = key_data.scopes.iter().map(|s| s.as_str().to_string()).collect();
+
+        let api_key = sqlx::query_as::<_, ApiKey>(
+            "INSERT INTO api_keys (id, name, prefix, key_hash, scopes, expires_at, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_at"
+        )
+        .bind(id)
+        .bind(&key_data.name)
+        .bind(prefix)
+        .bind(key_hash)
+        .bind(&scopes)
+        .bind(key_data.expires_at)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    // API anahtarını iptal et
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "api_keys"))]
+    pub async fn revoke_api_key(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE api_keys SET revoked_at = $1 WHERE id = $2 AND revoked_at IS NULL")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // API anahtarının son kullanılma zamanını güncelle (her istekte yazmamak için dakikada bir)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "UPDATE", db.sql.table = "api_keys"))]
+    pub async fn touch_api_key(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE api_keys SET last_used_at = NOW() WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ====== AUDIT LOG OPERATIONS ======
+
+    // Denetim kayıtlarını filtrele (en yeni kayıtlar önce)
+    #[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "audit_log"))]
+    pub async fn get_audit_log(&self, query: &AuditLogQuery, limit: i64) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            "SELECT id, occurred_at, actor, action, entity_type, entity_id, before, after FROM audit_log WHERE ($1::VARCHAR IS NULL OR entity_type = $1) AND ($2::UUID IS NULL OR entity_id = $2) AND ($3::VARCHAR IS NULL OR actor = $3) AND ($4::TIMESTAMPTZ IS NULL OR occurred_at >= $4) AND ($5::TIMESTAMPTZ IS NULL OR occurred_at < $5) ORDER BY occurred_at DESC LIMIT $6"
+        )
+        .bind(query.entity_type.map(|e| e.as_str()))
+        .bind(query.entity_id)
+        .bind(query.actor.as_deref())
+        .bind(query.from)
+        .bind(query.to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+/// Kalıcı olarak silinen kaydın denetim kaydındaki özeti
+#[derive(Serialize)]
+struct PurgedRecord {
+    id: Uuid,
+    /// Konumun silme çözünürlüğündeki H3 hücresi
+    h3_index: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PurgedRecord {
+    fn new(
+        id: Uuid,
+        location: Option<(f64, f64)>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let h3_index = location.and_then(|(lat, lng)| coords_to_h3(lat, lng, DEFAULT_ERASURE_RESOLUTION).ok());
+        Self { id, h3_index, created_at, updated_at, deleted_at }
+    }
+}
+
+/// Saklanacak konum: (enlem, boylam, varsayılan çözünürlükteki H3 index)
+type StoredLocation = (Option<f64>, Option<f64>, Option<String>);
+
+/// Gömülü migrasyonları veritabanında uygulanmış olanlarla karşılaştırır; migrasyon tablosu yoksa hepsi bekliyor sayılır
+#[tracing::instrument(skip_all, fields(otel.kind = "client", db.operation = "SELECT", db.sql.table = "_sqlx_migrations"))]
+pub async fn migration_status(pool: &PgPool) -> Result<MigrationCheck, sqlx::Error> {
+    let table_exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    let rows = if table_exists {
+        sqlx::query("SELECT version, success, checksum FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await?
+    } else {
+        vec![]
+    };
+    let applied: HashMap<i64, (bool, Vec<u8>)> = rows
+        .iter()
+        .map(|row| (row.get("version"), (row.get("success"), row.get("checksum"))))
+        .collect();
+
+    let mut check = MigrationCheck {
+        status: HealthStatus::Up,
+        expected: 0,
+        applied: 0,
+        pending: vec![],
+        failed: vec![],
+        error: None,
+    };
+    for migration in MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()) {
+        check.expected += 1;
+        match applied.get(&migration.version) {
+            Some((true, checksum)) if *checksum == *migration.checksum => check.applied += 1,
+            Some(_) => check.failed.push(migration.version),
+            None => check.pending.push(migration.version),
+        }
+    }
+    if !check.pending.is_empty() || !check.failed.is_empty() {
+        check.status = HealthStatus::Down;
+    }
+
+    Ok(check)
+}
+
+// Konumu verilen hassasiyete indirger
+fn apply_location_precision(
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    precision: LocationPrecision,
+) -> Result<StoredLocation, sqlx::Error> {
+    let (Some(lat), Some(lng)) = (latitude, longitude) else {
+        return Ok((None, None, None));
+    };
+    if precision == LocationPrecision::None {
+        return Ok((None, None, None));
+    }
+
+    let (lat, lng) = match precision.resolution() {
+        Some(resolution) => {
+            let (center_lat, center_lng, _) = snap_to_cell_center(lat, lng, resolution)
+                .map_err(|e| sqlx::Error::Protocol(format!("H3 hesaplama hatası: {}", e)))?;
+            (center_lat, center_lng)
+        }
+        None => (lat, lng),
+    };
+    Ok((Some(lat), Some(lng), coords_to_h3(lat, lng, DEFAULT_RESOLUTION).ok()))
+}
+
+// Kullanıcı kaydının konumunu daha kaba bir hassasiyete indirger (denetim kaydına yazılmadan önce)
+fn with_location_precision(mut user: User, precision: LocationPrecision) -> Result<User, sqlx::Error> {
+    if precision > user.location_precision {
+        (user.latitude, user.longitude, user.h3_index) = apply_location_precision(user.latitude, user.longitude, precision)?;
+        user.location_precision = precision;
+    }
+    Ok(user)
+}
+
+// Denetim kaydındaki kullanıcı görüntüsünü verilen hassasiyete indirger; değiştiyse true
+fn coarsen_snapshot(snapshot: &mut serde_json::Value, precision: LocationPrecision) -> Result<bool, sqlx::Error> {
+    let Some(fields) = snapshot.as_object_mut() else {
+        return Ok(false);
+    };
+    // Hassasiyet sütunundan önce yazılmış kayıtlar tam koordinat içerir
+    let current = fields
+        .get("location_precision")
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.parse::<LocationPrecision>().ok())
+        .unwrap_or_default();
+    if precision <= current {
+        return Ok(false);
+    }
+
+    let coordinate = |name: &str| fields.get(name).and_then(|value| value.as_f64());
+    let (latitude, longitude, h3_index) = apply_location_precision(coordinate("latitude"), coordinate("longitude"), precision)?;
+    fields.insert("latitude".to_string(), serde_json::json!(latitude));
+    fields.insert("longitude".to_string(), serde_json::json!(longitude));
+    fields.insert("h3_index".to_string(), serde_json::json!(h3_index));
+    fields.insert("location_precision".to_string(), serde_json::json!(precision.as_str()));
+    Ok(true)
+}
+
+// Kullanıcıların (verilirse yalnızca `user_id`'nin) denetim kayıtlarındaki konumları güncel hassasiyetlerine indirge
+async fn coarsen_audit_locations(tx: &mut Transaction<'_, Postgres>, user_id: Option<Uuid>) -> Result<u64, sqlx::Error> {
+    // Görüntüdeki hassasiyet kullanıcının güncel hassasiyetinden daha hassas olan kayıtlar
+    let rows = sqlx::query(
+        "SELECT a.id, a.before, a.after, u.location_precision FROM audit_log a JOIN users u ON u.id = a.entity_id WHERE a.entity_type = $1 AND ($2::UUID IS NULL OR a.entity_id = $2) AND (array_position($3::TEXT[], COALESCE(a.before->>'location_precision', 'exact')) < array_position($3::TEXT[], u.location_precision) OR array_position($3::TEXT[], COALESCE(a.after->>'location_precision', 'exact')) < array_position($3::TEXT[], u.location_precision))"
+    )
+    .bind(AuditEntity::User.as_str())
+    .bind(user_id)
+    .bind(["exact", "neighbourhood", "city", "none"])
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let (mut ids, mut befores, mut afters) = (Vec::new(), Vec::new(), Vec::new());
+    for row in rows {
+        let precision: LocationPrecision = row.get("location_precision");
+        let mut before: Option<serde_json::Value> = row.get("before");
+        let mut after: Option<serde_json::Value> = row.get("after");
+        let mut changed = false;
+        for snapshot in before.iter_mut().chain(after.iter_mut()) {
+            changed |= coarsen_snapshot(snapshot, precision)?;
+        }
+        if changed {
+            ids.push(row.get::<Uuid, _>("id"));
+            befores.push(before);
+            afters.push(after);
+        }
+    }
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    sqlx::query("SET LOCAL rustmicro.audit_scrub = 'on'")
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query(
+        "UPDATE audit_log a SET before = t.before, after = t.after FROM UNNEST($1::UUID[], $2::JSONB[], $3::JSONB[]) AS t(id, before, after) WHERE a.id = t.id"
+    )
+    .bind(&ids)
+    .bind(&befores)
+    .bind(&afters)
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query("SET LOCAL rustmicro.audit_scrub = 'off'")
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(ids.len() as u64)
+}
+
+// Değişiklik öncesi durumu okumak için kullanıcı satırını kilitle (deleted: silinmiş mi aranıyor)
+async fn lock_user(tx: &mut Transaction<'_, Postgres>, id: Uuid, deleted: bool) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>(
+        "SELECT id, name, email_ciphertext, email_key_id, email_hash, latitude, longitude, h3_index, location_precision, created_at, updated_at, deleted_at, erased_at FROM users WHERE id = $1 AND (deleted_at IS NOT NULL) = $2 FOR UPDATE"
+    )
+    .bind(id)
+    .bind(deleted)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+// Değişiklik öncesi durumu okumak için liman satırını kilitle (deleted: silinmiş mi aranıyor)
+async fn lock_port(tx: &mut Transaction<'_, Postgres>, id: Uuid, deleted: bool) -> Result<Option<Port>, sqlx::Error> {
+    sqlx::query_as::<_, Port>(
+        "SELECT id, name, code, country, city, latitude, longitude, h3_index, port_type, capacity, created_at, updated_at, deleted_at FROM ports WHERE id = $1 AND (deleted_at IS NOT NULL) = $2 FOR UPDATE"
+    )
+    .bind(id)
+    .bind(deleted)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+// Denetim kaydı ekle; mutasyonla aynı transaction'da yazılır, böylece biri olmadan diğeri kalıcı olmaz
+async fn record_audit<T: Serialize>(
+    tx: &mut Transaction<'_, Postgres>,
+    actor: &str,
+    action: AuditAction,
+    entity_type: AuditEntity,
+    entity_id: Uuid,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), sqlx::Error> {
+    let to_json = |value: Option<&T>| {
+        value
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| sqlx::Error::Protocol(format!("Denetim kaydı serileştirilemedi: {}", e)))
+    };
+
+    sqlx::query(
+        "INSERT INTO audit_log (id, occurred_at, actor, action, entity_type, entity_id, before, after) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+    )
+    .bind(Uuid::new_v4())
+    .bind(Utc::now())
+    .bind(actor)
+    .bind(action.as_str())
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .bind(to_json(before)?)
+    .bind(to_json(after)?)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
 }
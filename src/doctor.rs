@@ -0,0 +1,286 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use sqlx::postgres::{PgConnectOptions, PgPool};
+
+use crate::config::AppConfig;
+use crate::crypto::EmailCipher;
+use crate::database::{self, Database};
+use crate::models::HealthStatus;
+use crate::oidc::OidcValidator;
+use crate::setup;
+
+/// Desteklenen en eski PostgreSQL sürümü (`server_version_num` biçiminde)
+const MIN_SERVER_VERSION: i32 = 120000;
+
+/// Bir kontrolün sonucu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Tek bir tanılama kontrolünün sonucu; sorun varsa ne yapılması gerektiğini söyleyen bir öneri taşır
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Ok, message: message.into(), hint: None }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Warning, message: message.into(), hint: Some(hint.into()) }
+    }
+
+    fn error(check: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Error, message: message.into(), hint: Some(hint.into()) }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self.severity {
+            Severity::Ok => "✅",
+            Severity::Warning => "⚠️ ",
+            Severity::Error => "❌",
+        };
+        write!(f, "{} {}: {}", icon, self.check, self.message)?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n   → {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Konfigürasyonu, veritabanını ve dış bağımlılıkları kontrol eder
+///
+/// Kontroller birbirine bağlı olduğunda (örn: veritabanına bağlanılamıyorsa migrasyonlar) sonrakiler atlanır;
+/// her sorun için ayrı bir tanı döner.
+pub async fn run(config_file: Option<&Path>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let (config, errors) = AppConfig::resolve(config_file);
+    if errors.is_empty() {
+        diagnostics.push(Diagnostic::ok("Konfigürasyon", "geçerli"));
+    } else {
+        for error in &errors {
+            diagnostics.push(Diagnostic::error(
+                "Konfigürasyon",
+                error.clone(),
+                "config.example.toml ve .env.example'daki açıklamalara bakın; etkin değerler için: rustmicro config",
+            ));
+        }
+    }
+
+    diagnostics.push(check_server_address(&config));
+
+    if let Some(pool) = check_database(&config, &mut diagnostics).await {
+        diagnostics.push(check_server_version(&pool).await);
+        let migrated = check_migrations(&pool, &mut diagnostics).await;
+        if migrated {
+            if let Ok(cipher) = EmailCipher::from_config(&config) {
+                diagnostics.push(check_email_keys(Database::new(pool.clone(), cipher)).await);
+            }
+        }
+        pool.close().await;
+    }
+
+    if let Ok(Some(validator)) = OidcValidator::from_config(&config) {
+        diagnostics.push(match validator.jwks().refresh().await {
+            Ok(keys) => Diagnostic::ok("OIDC", format!("JWKS yüklendi ({} anahtar)", keys)),
+            Err(e) => Diagnostic::error(
+                "OIDC",
+                format!("JWKS yüklenemedi: {}", e),
+                "OIDC_JWKS_URL/OIDC_JWKS_PATH değerini ve SSO sunucusuna erişimi kontrol edin",
+            ),
+        });
+    }
+
+    diagnostics
+}
+
+// Sunucunun dinleyeceği adres boşta mı
+fn check_server_address(config: &AppConfig) -> Diagnostic {
+    let address = config.server_address();
+    match std::net::TcpListener::bind(&address) {
+        Ok(_) => Diagnostic::ok("Sunucu adresi", format!("{} kullanılabilir", address)),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => Diagnostic::warning(
+            "Sunucu adresi",
+            format!("{} kullanımda", address),
+            "Uygulama zaten çalışıyor olabilir; değilse SERVER_PORT'u değiştirin veya portu tutan süreci durdurun",
+        ),
+        Err(e) => Diagnostic::error(
+            "Sunucu adresi",
+            format!("{} dinlenemiyor: {}", address, e),
+            "SERVER_HOST'un bu makinedeki bir arayüz olduğundan emin olun (örn: 0.0.0.0 veya 127.0.0.1)",
+        ),
+    }
+}
+
+// Veritabanına bağlanır; bağlanılamazsa nedenine göre bir tanı ekler
+async fn check_database(config: &AppConfig, diagnostics: &mut Vec<Diagnostic>) -> Option<PgPool> {
+    const CHECK: &str = "Veritabanı bağlantısı";
+
+    let Ok(options) = PgConnectOptions::from_str(&config.database.url) else {
+        // Geçersiz URL konfigürasyon kontrolünde raporlandı
+        return None;
+    };
+    let target = format!(
+        "{}:{}/{}",
+        options.get_host(),
+        options.get_port(),
+        options.get_database().unwrap_or("postgres")
+    );
+
+    match setup::connect(config).await {
+        Ok(pool) => {
+            diagnostics.push(Diagnostic::ok(CHECK, format!("{} bağlantısı başarılı", target)));
+            Some(pool)
+        }
+        Err(e) => {
+            diagnostics.push(connection_diagnostic(&e, &target));
+            None
+        }
+    }
+}
+
+// Bağlantı hatasını yapılabilecek bir öneriye çevirir
+fn connection_diagnostic(error: &sqlx::Error, target: &str) -> Diagnostic {
+    const CHECK: &str = "Veritabanı bağlantısı";
+
+    match error {
+        sqlx::Error::Database(e) => match e.code().as_deref() {
+            // invalid_catalog_name
+            Some("3D000") => Diagnostic::error(CHECK, format!("{} veritabanı mevcut değil", target), "Oluşturmak için: rustmicro db create"),
+            // invalid_password, invalid_authorization_specification
+            Some("28P01") | Some("28000") => Diagnostic::error(
+                CHECK,
+                format!("{} kimlik doğrulaması başarısız: {}", target, e.message()),
+                "DATABASE_URL'deki kullanıcı adı ve parolayı, sunucunun pg_hba.conf kurallarını kontrol edin",
+            ),
+            _ => Diagnostic::error(CHECK, format!("{}: {}", target, e.message()), "PostgreSQL sunucu loglarını kontrol edin"),
+        },
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => Diagnostic::error(
+            CHECK,
+            format!("{} adresine ulaşılamıyor ({})", target, error),
+            "PostgreSQL'in çalıştığından ve DATABASE_URL'deki host/port'un doğru olduğundan emin olun (örn: systemctl status postgresql)",
+        ),
+        sqlx::Error::Tls(_) => Diagnostic::error(
+            CHECK,
+            format!("{} TLS bağlantısı kurulamadı ({})", target, error),
+            "DATABASE_URL'deki sslmode değerini sunucunun TLS ayarıyla uyumlu hale getirin",
+        ),
+        _ => Diagnostic::error(CHECK, format!("{}: {}", target, error), "DATABASE_URL değerini kontrol edin"),
+    }
+}
+
+async fn check_server_version(pool: &PgPool) -> Diagnostic {
+    const CHECK: &str = "PostgreSQL sürümü";
+
+    let version: Result<String, _> = sqlx::query_scalar("SHOW server_version_num").fetch_one(pool).await;
+    match version.map(|v| v.parse::<i32>()) {
+        Ok(Ok(version)) if version >= MIN_SERVER_VERSION => {
+            Diagnostic::ok(CHECK, format!("{}.{}", version / 10000, version % 100))
+        }
+        Ok(Ok(version)) => Diagnostic::error(
+            CHECK,
+            format!("{}.{} desteklenmiyor", version / 10000, version % 100),
+            format!("PostgreSQL {} veya üzerine yükseltin", MIN_SERVER_VERSION / 10000),
+        ),
+        Ok(Err(e)) => Diagnostic::warning(CHECK, format!("sürüm ayrıştırılamadı: {}", e), "PostgreSQL sürümünü manuel olarak kontrol edin"),
+        Err(e) => Diagnostic::warning(CHECK, format!("sürüm okunamadı: {}", e), "PostgreSQL sürümünü manuel olarak kontrol edin"),
+    }
+}
+
+// Migrasyon durumunu raporlar; şema güncelse `true` döner
+async fn check_migrations(pool: &PgPool, diagnostics: &mut Vec<Diagnostic>) -> bool {
+    const CHECK: &str = "Migrasyonlar";
+
+    let check = match database::migration_status(pool).await {
+        Ok(check) => check,
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(CHECK, format!("durum okunamadı: {}", e), "Bağlanan kullanıcının _sqlx_migrations tablosunu okuma yetkisini kontrol edin"));
+            return false;
+        }
+    };
+    if check.status == HealthStatus::Up {
+        diagnostics.push(Diagnostic::ok(CHECK, format!("{}/{} uygulandı", check.applied, check.expected)));
+        return true;
+    }
+
+    if !check.failed.is_empty() {
+        diagnostics.push(Diagnostic::error(
+            CHECK,
+            format!("başarısız veya uygulandıktan sonra değiştirilmiş: {:?}", check.failed),
+            "Uygulanmış migrasyon dosyaları değiştirilmemeli; dosyayı geri alın veya _sqlx_migrations kaydını inceleyin",
+        ));
+    }
+    if !check.pending.is_empty() {
+        diagnostics.push(Diagnostic::warning(
+            CHECK,
+            format!("{} migrasyon bekliyor: {:?}", check.pending.len(), check.pending),
+            "Uygulamak için: rustmicro migrate (serve da başlangıçta uygular)",
+        ));
+    }
+    false
+}
+
+// Email'lerin mevcut anahtarlarla çözülebildiğini kontrol eder
+async fn check_email_keys(db: Database) -> Diagnostic {
+    const CHECK: &str = "Email şifreleme";
+
+    let usage = match db.email_key_usage().await {
+        Ok(usage) => usage,
+        Err(e) => return Diagnostic::error(CHECK, format!("anahtar kullanımı okunamadı: {}", e), "users tablosunu okuma yetkisini kontrol edin"),
+    };
+    let cipher = db.cipher();
+
+    let mut unknown = Vec::new();
+    let mut pending = 0;
+    for (key_id, plaintext, count) in usage {
+        match key_id {
+            Some(key_id) if !cipher.has_key(&key_id) => unknown.push(format!("{} ({} kullanıcı)", key_id, count)),
+            Some(key_id) if key_id != cipher.active_key_id() => pending += count,
+            _ if plaintext => pending += count,
+            _ => {}
+        }
+    }
+
+    if !unknown.is_empty() {
+        Diagnostic::error(
+            CHECK,
+            format!("tanımlı olmayan anahtarlarla şifrelenmiş email'ler var: {}", unknown.join(", ")),
+            "Eski anahtarları EMAIL_ENCRYPTION_KEYS listesine (etkin anahtardan sonra) geri ekleyin",
+        )
+    } else if pending > 0 {
+        Diagnostic::warning(
+            CHECK,
+            format!("{} kullanıcının email'i etkin anahtara ({}) taşınmamış", pending, cipher.active_key_id()),
+            "serve başlangıçta bu email'leri etkin anahtarla yeniden şifreler",
+        )
+    } else {
+        Diagnostic::ok(CHECK, format!("tüm email'ler etkin anahtarla ({}) şifreli", cipher.active_key_id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreachable_database_suggests_checking_server() {
+        let error = sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        let diagnostic = connection_diagnostic(&error, "localhost:5432/rustmicro");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert!(diagnostic.message.contains("ulaşılamıyor"));
+        assert!(diagnostic.hint.unwrap().contains("host/port"));
+    }
+}
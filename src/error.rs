@@ -0,0 +1,240 @@
+use std::fmt;
+
+use actix_web::http::{header, StatusCode};
+use actix_web::{HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::validation::{FieldError, ValidationErrors};
+
+/// RFC 7807 yanıtlarının içerik tipi
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// RFC 7807 problem detayı
+///
+/// `code` alanı istemcilerin güvenebileceği sabit bir hata kodudur; `type` ise bu koddan türetilir.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    /// Problem tipini tanımlayan URI (örn: "urn:rustmicro:problem:email_taken")
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Problemin kısa özeti
+    pub title: String,
+    /// HTTP durum kodu
+    pub status: u16,
+    /// Bu örneğe özgü açıklama
+    pub detail: String,
+    /// Sabit, makine tarafından okunabilir hata kodu
+    pub code: String,
+    /// Alan bazında doğrulama hataları (yalnızca 422 yanıtlarında)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+}
+
+/// Tüm handler'ların ortak hata tipi
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest { code: &'static str, detail: String },
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict { code: &'static str, detail: String },
+    Validation(Vec<FieldError>),
+    TooManyRequests { code: &'static str, detail: String },
+    Database(sqlx::Error),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn bad_request(code: &'static str, detail: impl Into<String>) -> Self {
+        AppError::BadRequest { code, detail: detail.into() }
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        AppError::NotFound(detail.into())
+    }
+
+    pub fn conflict(code: &'static str, detail: impl Into<String>) -> Self {
+        AppError::Conflict { code, detail: detail.into() }
+    }
+
+    /// İstemcilerin güvenebileceği sabit hata kodu
+    pub fn code(&self) -> &'static str {
+        self.parts().1
+    }
+
+    /// Durum kodu, sabit hata kodu ve istemciye gösterilecek açıklama
+    fn parts(&self) -> (StatusCode, &'static str, String) {
+        match self {
+            AppError::BadRequest { code, detail } => (StatusCode::BAD_REQUEST, code, detail.clone()),
+            AppError::Unauthorized(detail) => (StatusCode::UNAUTHORIZED, "unauthorized", detail.clone()),
+            AppError::Forbidden(detail) => (StatusCode::FORBIDDEN, "forbidden", detail.clone()),
+            AppError::NotFound(detail) => (StatusCode::NOT_FOUND, "not_found", detail.clone()),
+            AppError::Conflict { code, detail } => (StatusCode::CONFLICT, code, detail.clone()),
+            AppError::Validation(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "validation_failed",
+                "İstek doğrulanamadı".to_string(),
+            ),
+            AppError::TooManyRequests { code, detail } => (StatusCode::TOO_MANY_REQUESTS, code, detail.clone()),
+            AppError::Database(e) => classify_database_error(e),
+            AppError::Internal(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Beklenmeyen bir hata oluştu".to_string(),
+            ),
+        }
+    }
+}
+
+/// sqlx hatalarını PostgreSQL SQLSTATE kodlarına göre HTTP durumlarına eşler
+fn classify_database_error(error: &sqlx::Error) -> (StatusCode, &'static str, String) {
+    match error {
+        sqlx::Error::RowNotFound => (StatusCode::NOT_FOUND, "not_found", "Kayıt bulunamadı".to_string()),
+        sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "database_unavailable",
+            "Veritabanına şu anda ulaşılamıyor".to_string(),
+        ),
+        sqlx::Error::Database(db_error) => match db_error.code().as_deref() {
+            // unique_violation
+            Some("23505") => match db_error.constraint() {
+                Some("users_email_key") => (
+                    StatusCode::CONFLICT,
+                    "email_taken",
+                    "Bu email adresi zaten kullanımda".to_string(),
+                ),
+                Some("ports_code_key") => (
+                    StatusCode::CONFLICT,
+                    "port_code_taken",
+                    "Bu liman kodu zaten kullanımda".to_string(),
+                ),
+                _ => (StatusCode::CONFLICT, "already_exists", "Kayıt zaten mevcut".to_string()),
+            },
+            // foreign_key_violation
+            Some("23503") => (
+                StatusCode::CONFLICT,
+                "foreign_key_violation",
+                "Kayıt başka bir kayıtla ilişkili olduğu için işlem yapılamadı".to_string(),
+            ),
+            // check_violation, not_null_violation
+            Some("23514") | Some("23502") => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "constraint_violation",
+                format!(
+                    "Veri kısıtı ihlal edildi{}",
+                    db_error.constraint().map(|c| format!(": {}", c)).unwrap_or_default()
+                ),
+            ),
+            // invalid_text_representation, numeric_value_out_of_range, string_data_right_truncation
+            Some("22P02") | Some("22003") | Some("22001") => (
+                StatusCode::BAD_REQUEST,
+                "invalid_value",
+                "Geçersiz değer".to_string(),
+            ),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "database_error", "Veritabanı hatası".to_string()),
+        },
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "database_error", "Veritabanı hatası".to_string()),
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Database(e) => write!(f, "Database error: {}", e),
+            AppError::Internal(e) => write!(f, "Internal error: {}", e),
+            _ => {
+                let (status, code, detail) = self.parts();
+                write!(f, "{} {}: {}", status.as_u16(), code, detail)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        AppError::Database(error)
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors.errors)
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        self.parts().0
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let (status, code, detail) = self.parts();
+
+        // Sunucu tarafı hataların ayrıntısı yalnızca loglanır, istemciye gönderilmez
+        if status.is_server_error() {
+            tracing::error!(error = %self, "Sunucu hatası");
+        }
+
+        let errors = match self {
+            AppError::Validation(errors) => Some(errors.clone()),
+            _ => None,
+        };
+
+        let problem = ProblemDetails {
+            problem_type: format!("urn:rustmicro:problem:{}", code),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail,
+            code: code.to_string(),
+            errors,
+        };
+
+        let mut response = HttpResponse::build(status);
+        response.content_type(PROBLEM_JSON);
+        if status == StatusCode::UNAUTHORIZED {
+            response.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+        }
+        response.body(serde_json::to_string(&problem).unwrap_or_default())
+    }
+}
+
+/// Gövdesi JSON olarak çözümlenemeyen istekler için problem yanıtı
+pub fn json_error_handler(err: actix_web::error::JsonPayloadError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+    AppError::bad_request("malformed_body", err.to_string()).into()
+}
+
+/// Sorgu parametreleri çözümlenemeyen istekler için problem yanıtı
+pub fn query_error_handler(err: actix_web::error::QueryPayloadError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+    AppError::bad_request("invalid_query", err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[actix_web::test]
+    async fn test_validation_problem_response() {
+        let mut errors = ValidationErrors::default();
+        errors.add("email", "invalid_email", "Geçerli bir e-posta adresi olmalı");
+        let response = AppError::from(errors).error_response();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), PROBLEM_JSON);
+
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["code"], "validation_failed");
+        assert_eq!(body["type"], "urn:rustmicro:problem:validation_failed");
+        assert_eq!(body["errors"][0]["field"], "email");
+    }
+
+    #[test]
+    fn test_row_not_found_maps_to_404() {
+        let error = AppError::from(sqlx::Error::RowNotFound);
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(AppError::from(sqlx::Error::PoolTimedOut).status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
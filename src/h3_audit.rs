@@ -0,0 +1,215 @@
+use std::sync::Mutex;
+
+use actix_web::web;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::h3_utils::{cell_resolution, coords_to_h3, DEFAULT_RESOLUTION};
+use crate::models::{AuditEntity, H3AuditCounts, H3AuditStatus, H3Mismatch, JobState};
+
+/// Varsayılan batch boyutu
+pub const DEFAULT_BATCH_SIZE: u32 = 500;
+
+/// En büyük batch boyutu
+pub const MAX_BATCH_SIZE: u32 = 10_000;
+
+/// Raporda listelenecek en fazla uyuşmazlık
+const MAX_REPORTED_MISMATCHES: usize = 1000;
+
+/// Bir batch'in denetim sonucu
+pub struct H3AuditBatch {
+    /// Batch'teki son kaydın kimliği (sonraki batch bundan sonra başlar)
+    pub last_id: Option<Uuid>,
+    pub checked: u64,
+    pub mismatches: Vec<H3Mismatch>,
+}
+
+/// Kaydın koordinatlarından `DEFAULT_RESOLUTION`'da beklenen index'i hesaplar; kayıtlı index uyuşmuyorsa
+/// uyuşmazlığı döndürür. Koordinatı olmayan kayıtların index'i de boş olmalıdır.
+pub fn check_record(
+    entity: AuditEntity,
+    id: Uuid,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    stored: Option<String>,
+) -> Option<H3Mismatch> {
+    let expected = match (latitude, longitude) {
+        (Some(lat), Some(lng)) => coords_to_h3(lat, lng, DEFAULT_RESOLUTION).ok(),
+        _ => None,
+    };
+    if stored == expected {
+        return None;
+    }
+
+    Some(H3Mismatch {
+        entity,
+        id,
+        latitude,
+        longitude,
+        stored_resolution: stored.as_deref().and_then(cell_resolution),
+        stored,
+        expected,
+        fixed: false,
+    })
+}
+
+/// Uyuşmazlık yeniden hesaplanarak düzeltilebilir mi: koordinatlar geçersizse beklenen index de yoktur
+pub fn is_fixable(mismatch: &H3Mismatch) -> bool {
+    mismatch.expected.is_some() || mismatch.latitude.is_none() || mismatch.longitude.is_none()
+}
+
+/// Yeni bir denetim işinin başlangıç durumu
+pub fn new_status(fix: bool, batch_size: u32) -> H3AuditStatus {
+    H3AuditStatus {
+        id: Uuid::new_v4(),
+        state: JobState::Running,
+        fix,
+        resolution: DEFAULT_RESOLUTION,
+        batch_size,
+        started_at: Utc::now(),
+        finished_at: None,
+        users: H3AuditCounts::default(),
+        ports: H3AuditCounts::default(),
+        mismatches: vec![],
+        mismatches_truncated: false,
+        error: None,
+    }
+}
+
+/// Tüm kullanıcı ve limanların h3_index'ini batch'ler halinde denetler; `fix` ise uyuşmayanları düzeltir
+///
+/// Her batch'ten sonra `on_progress` güncel durumla çağrılır. Sonuçta durum `Completed` veya `Failed` olur.
+pub async fn run(db: &Database, status: &mut H3AuditStatus, mut on_progress: impl FnMut(&H3AuditStatus)) {
+    if let Err(e) = audit_all(db, status, &mut on_progress).await {
+        tracing::error!(job_id = %status.id, error = %e, "H3 denetimi başarısız");
+        status.state = JobState::Failed;
+        status.error = Some(e.to_string());
+    } else {
+        status.state = JobState::Completed;
+    }
+    status.finished_at = Some(Utc::now());
+
+    tracing::info!(
+        job_id = %status.id,
+        fix = status.fix,
+        users_mismatched = status.users.mismatched,
+        ports_mismatched = status.ports.mismatched,
+        fixed = status.users.fixed + status.ports.fixed,
+        "H3 denetimi tamamlandı"
+    );
+    on_progress(status);
+}
+
+async fn audit_all(
+    db: &Database,
+    status: &mut H3AuditStatus,
+    on_progress: &mut impl FnMut(&H3AuditStatus),
+) -> Result<(), sqlx::Error> {
+    status.users.total = db.count_h3_records(AuditEntity::User).await?;
+    status.ports.total = db.count_h3_records(AuditEntity::Port).await?;
+    on_progress(status);
+
+    for entity in [AuditEntity::User, AuditEntity::Port] {
+        let mut after = None;
+        loop {
+            let batch = db
+                .audit_h3_batch(entity, after, i64::from(status.batch_size), status.fix)
+                .await?;
+
+            let counts = match entity {
+                AuditEntity::User => &mut status.users,
+                AuditEntity::Port => &mut status.ports,
+            };
+            counts.checked += batch.checked;
+            counts.mismatched += batch.mismatches.len() as u64;
+            counts.fixed += batch.mismatches.iter().filter(|m| m.fixed).count() as u64;
+
+            for mismatch in batch.mismatches {
+                if status.mismatches.len() < MAX_REPORTED_MISMATCHES {
+                    status.mismatches.push(mismatch);
+                } else {
+                    status.mismatches_truncated = true;
+                }
+            }
+            on_progress(status);
+
+            match batch.last_id {
+                Some(last_id) if batch.checked == u64::from(status.batch_size) => after = Some(last_id),
+                _ => break,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Admin endpoint'inden başlatılan denetim işlerinin durumu; aynı anda tek iş çalışır
+#[derive(Default)]
+pub struct H3AuditJobs {
+    latest: Mutex<Option<H3AuditStatus>>,
+}
+
+impl H3AuditJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Son (veya çalışmakta olan) işin durumu
+    pub fn latest(&self) -> Option<H3AuditStatus> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Arka planda yeni bir iş başlatır; başka bir iş çalışıyorsa `None` döner
+    pub fn start(jobs: web::Data<Self>, db: web::Data<Database>, fix: bool, batch_size: u32) -> Option<H3AuditStatus> {
+        let mut status = new_status(fix, batch_size);
+        {
+            let mut latest = jobs.latest.lock().unwrap();
+            if latest.as_ref().is_some_and(|job| job.state == JobState::Running) {
+                return None;
+            }
+            *latest = Some(status.clone());
+        }
+
+        let started = status.clone();
+        actix_web::rt::spawn(async move {
+            run(&db, &mut status, |progress| {
+                *jobs.latest.lock().unwrap() = Some(progress.clone());
+            })
+            .await;
+        });
+        Some(started)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_record_detects_stale_indexes() {
+        let id = Uuid::new_v4();
+        let (lat, lng) = (40.977, 28.685);
+        let current = coords_to_h3(lat, lng, DEFAULT_RESOLUTION).unwrap();
+
+        // Güncel index
+        assert!(check_record(AuditEntity::Port, id, Some(lat), Some(lng), Some(current.clone())).is_none());
+
+        // Başka bir çözünürlükte hesaplanmış index
+        let stale = coords_to_h3(lat, lng, 9).unwrap();
+        let mismatch = check_record(AuditEntity::Port, id, Some(lat), Some(lng), Some(stale)).unwrap();
+        assert_eq!(mismatch.stored_resolution, Some(9));
+        assert_eq!(mismatch.expected.as_deref(), Some(current.as_str()));
+        assert!(is_fixable(&mismatch));
+
+        // Konumu silinmiş ama index'i kalmış kullanıcı
+        let orphan = check_record(AuditEntity::User, id, None, None, Some(current)).unwrap();
+        assert_eq!(orphan.expected, None);
+        assert!(is_fixable(&orphan));
+
+        // Geçersiz koordinat düzeltilemez
+        let invalid = check_record(AuditEntity::Port, id, Some(f64::NAN), Some(lng), Some("geçersiz".to_string())).unwrap();
+        assert_eq!(invalid.stored_resolution, None);
+        assert!(!is_fixable(&invalid));
+    }
+}
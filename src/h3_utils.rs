@@ -1,4 +1,6 @@
-use h3o::{CellIndex, LatLng, Resolution};
+use std::collections::{HashMap, HashSet};
+
+use h3o::{CellIndex, LatLng, Resolution, VertexIndex};
 
 /// Latitude ve longitude'dan H3 index'i oluşturur
 pub fn coords_to_h3(lat: f64, lng: f64, resolution: u8) -> Result<String, Box<dyn std::error::Error>> {
@@ -15,7 +17,20 @@ pub fn h3_to_coords(h3_string: &str) -> Result<(f64, f64), Box<dyn std::error::E
     Ok((latlng.lat(), latlng.lng()))
 }
 
+/// Koordinatı verilen çözünürlükteki hücrenin merkezine indirger: (enlem, boylam, hücre)
+pub fn snap_to_cell_center(lat: f64, lng: f64, resolution: u8) -> Result<(f64, f64, String), Box<dyn std::error::Error>> {
+    let cell = coords_to_h3(lat, lng, resolution)?;
+    let (center_lat, center_lng) = h3_to_coords(&cell)?;
+    Ok((center_lat, center_lng, cell))
+}
+
+/// H3 index'inin çözünürlüğü; geçerli bir hücre değilse `None`
+pub fn cell_resolution(h3_string: &str) -> Option<u8> {
+    h3_string.parse::<CellIndex>().ok().map(|cell| u8::from(cell.resolution()))
+}
+
 /// H3 cell'in komşularını getirir
+#[allow(dead_code)]
 pub fn get_neighbors(h3_string: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let cell: CellIndex = h3_string.parse()?;
     let neighbors: Vec<String> = cell
@@ -27,12 +42,14 @@ pub fn get_neighbors(h3_string: &str) -> Result<Vec<String>, Box<dyn std::error:
 }
 
 /// H3 cell'in alanını km² cinsinden getirir
+#[allow(dead_code)]
 pub fn get_cell_area_km2(h3_string: &str) -> Result<f64, Box<dyn std::error::Error>> {
     let cell: CellIndex = h3_string.parse()?;
     Ok(cell.area_km2())
 }
 
 /// İki H3 cell arasındaki mesafeyi hesaplar
+#[allow(dead_code)]
 pub fn distance_between_cells(h3_1: &str, h3_2: &str) -> Result<u32, Box<dyn std::error::Error>> {
     let cell1: CellIndex = h3_1.parse()?;
     let cell2: CellIndex = h3_2.parse()?;
@@ -41,6 +58,7 @@ pub fn distance_between_cells(h3_1: &str, h3_2: &str) -> Result<u32, Box<dyn std
 }
 
 /// H3 resolution seviyelerine göre açıklama
+#[allow(dead_code)]
 pub fn get_resolution_info(resolution: u8) -> &'static str {
     match resolution {
         0 => "~4,250,000 km² - Kıta düzeyinde",
@@ -95,6 +113,7 @@ pub fn get_k_ring(h3_string: &str, k: u32) -> Result<Vec<String>, Box<dyn std::e
 }
 
 /// Gerçek km mesafesine göre uygun k değerini hesaplar
+#[allow(dead_code)]
 pub fn calculate_k_for_distance(distance_km: f64, resolution: u8) -> u32 {
     // H3 resolution'a göre ortalama cell boyutları (km cinsinden)
     let avg_cell_size_km = match resolution {
@@ -122,6 +141,78 @@ pub fn calculate_k_for_distance(distance_km: f64, resolution: u8) -> u32 {
     k.max(1) // En az 1 ring
 }
 
+/// GeoJSON `MultiPolygon` koordinatları: `[polygon][halka][nokta][boylam, enlem]`
+pub type MultiPolygonCoordinates = Vec<Vec<Vec<[f64; 2]>>>;
+
+/// Hücre kümesini birleştirip GeoJSON `MultiPolygon` koordinatlarına çevirir
+///
+/// Komşu hücreler arasındaki ortak kenarlar atılır; kalan kenarlar kapalı halkalara bağlanır. Dış halkalar saat
+/// yönünün tersine, delikler saat yönünde döner (RFC 7946) ve her delik onu içeren dış halkanın polygonuna eklenir.
+/// Antimeridyeni kesen halkalar bölünmez.
+pub fn cells_to_multipolygon(cells: &[String]) -> Result<MultiPolygonCoordinates, Box<dyn std::error::Error>> {
+    let cells = cells
+        .iter()
+        .map(|cell| cell.parse::<CellIndex>())
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    // Her hücrenin köşeleri saat yönünün tersine sıralıdır; komşu hücre aynı kenarı ters yönde içerir
+    let mut edges = HashSet::new();
+    for cell in &cells {
+        let vertexes: Vec<VertexIndex> = cell.vertexes().collect();
+        for (i, from) in vertexes.iter().enumerate() {
+            edges.insert((*from, vertexes[(i + 1) % vertexes.len()]));
+        }
+    }
+    let mut next: HashMap<VertexIndex, VertexIndex> = edges
+        .iter()
+        .filter(|(from, to)| !edges.contains(&(*to, *from)))
+        .map(|(from, to)| (*from, *to))
+        .collect();
+
+    let mut outers = Vec::new();
+    let mut holes = Vec::new();
+    while let Some(&start) = next.keys().next() {
+        let mut ring = Vec::new();
+        let mut vertex = start;
+        while let Some(to) = next.remove(&vertex) {
+            let latlng = LatLng::from(vertex);
+            ring.push([latlng.lng(), latlng.lat()]);
+            vertex = to;
+        }
+        ring.push(ring[0]);
+        if signed_area(&ring) >= 0.0 {
+            outers.push(vec![ring]);
+        } else {
+            holes.push(ring);
+        }
+    }
+
+    for hole in holes {
+        let [lng, lat] = hole[0];
+        if let Some(polygon) = outers.iter_mut().find(|polygon| ring_contains(&polygon[0], lng, lat)) {
+            polygon.push(hole);
+        }
+    }
+    Ok(outers)
+}
+
+// Halkanın işaretli alanı (shoelace); saat yönünün tersine dönen halkalar için pozitif
+fn signed_area(ring: &[[f64; 2]]) -> f64 {
+    ring.windows(2).map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1]).sum::<f64>() / 2.0
+}
+
+// Nokta halkanın içinde mi (ışın atma)
+fn ring_contains(ring: &[[f64; 2]], lng: f64, lat: f64) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let ([x1, y1], [x2, y2]) = (w[0], w[1]);
+        if (y1 > lat) != (y2 > lat) && lng < (x2 - x1) * (lat - y1) / (y2 - y1) + x1 {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +233,34 @@ mod tests {
         assert!((lat - 41.0082).abs() < 0.01);
         assert!((lng - 28.9784).abs() < 0.01);
     }
+
+    #[test]
+    fn test_snap_to_cell_center() {
+        let (lat, lng, cell) = snap_to_cell_center(41.0082, 28.9784, 5).unwrap();
+        assert_eq!(coords_to_h3(lat, lng, 5).unwrap(), cell);
+        assert_eq!(snap_to_cell_center(41.02, 28.99, 5).unwrap().2, cell);
+    }
+
+    #[test]
+    fn test_cells_to_multipolygon_dissolves_shared_edges() {
+        let center = coords_to_h3(41.0082, 28.9784, 7).unwrap();
+        let disk = get_k_ring(&center, 1).unwrap();
+
+        // 7 hücrelik disk tek bir dış halkadır
+        let polygons = cells_to_multipolygon(&disk).unwrap();
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 1);
+        assert_eq!(polygons[0][0].len(), 18 + 1);
+
+        // Merkezi çıkarılmış halka bir delik içerir
+        let ring: Vec<String> = disk.into_iter().filter(|cell| *cell != center).collect();
+        let polygons = cells_to_multipolygon(&ring).unwrap();
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 2);
+        assert!(signed_area(&polygons[0][1]) < 0.0);
+
+        // Birbirine değmeyen hücreler ayrı polygonlardır
+        let far = coords_to_h3(39.9334, 32.8597, 7).unwrap();
+        assert_eq!(cells_to_multipolygon(&[center, far]).unwrap().len(), 2);
+    }
 }
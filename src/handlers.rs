@@ -1,15 +1,37 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use futures_util::{stream, StreamExt};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::auth::{caller_key, generate_api_key, hash_api_key, Principal, Scope};
+use crate::config::AppConfig;
+use crate::metrics::Metrics;
+use crate::privacy::{anonymize_cells, top_regions, PrivacyBudget, BUDGET_WINDOW_HOURS};
+use crate::bulk::{encode_users, LineSplitter, UserImporter, EXPORT_PAGE_SIZE};
 use crate::database::Database;
-use crate::models::{CreateUser, UpdateUser, UserResponse, CreatePort, PortResponse, FindNearestPortRequest, H3HeatmapResponse};
+use crate::h3_audit::{H3AuditJobs, DEFAULT_BATCH_SIZE};
+use crate::catchment::{self, CatchmentCache, CatchmentLookup};
+use crate::heatmap;
+use crate::h3_utils::cells_to_multipolygon;
+use crate::error::AppError;
+use crate::validation::{Valid, DEFAULT_ERASURE_RESOLUTION};
+use crate::models::{CreateUser, UpdateUser, UserResponse, CreatePort, PortResponse, FindNearestPortRequest, IncludeDeletedQuery, AuditLogQuery, AuditEntity, EraseUserRequest, SubjectAccessExport, BulkFormat, ImportUsersQuery, ExportUsersQuery, HeatmapQuery, HealthStatus, DatabaseCheck, MigrationCheck, PoolCheck, ReadinessChecks, ReadinessResponse, HeatmapPrivacy, CreateApiKey, ApiKeyResponse, CreatedApiKeyResponse, H3AuditRequest, StatsQuery, UserStats, PortStats, CatchmentQuery, CoverageGapQuery, CoverageFormat, CoverageGapReport, CoverageGapFeature, CoverageGapCollection, GeoJsonMultiPolygon, HeatmapLayer, HeatmapMetric, HeatmapCompareQuery, HeatmapComparison, TimeRange, PORT_TYPES};
+
+/// Handler sonuç tipi; hatalar `application/problem+json` olarak döner
+pub type ApiResult = Result<HttpResponse, AppError>;
+
+/// Path'ten gelen UUID'yi çözümler
+fn parse_id(raw: &str, entity: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(raw).map_err(|_| AppError::bad_request("invalid_id", format!("Geçersiz {} ID formatı", entity)))
+}
 
 /// API ana sayfası
-/// 
+///
 /// Bu endpoint, API'nin çalıştığını ve temel bilgileri döndürür.
-/// 
+///
 /// # Returns
-/// 
+///
 /// * `HttpResponse` - JSON formatında API bilgisi
 #[utoipa::path(
     get,
@@ -18,7 +40,8 @@ use crate::models::{CreateUser, UpdateUser, UserResponse, CreatePort, PortRespon
         (status = 200, description = "API çalışıyor", body = ApiResponse)
     )
 )]
-pub async fn index() -> Result<HttpResponse> {
+#[tracing::instrument(skip_all)]
+pub async fn index() -> ApiResult {
     let response = ApiResponse {
         message: "Rust Microservice API'ye hoş geldiniz!".to_string(),
         status: "success".to_string(),
@@ -26,22 +49,20 @@ pub async fn index() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// Sağlık kontrolü
-/// 
-/// Bu endpoint, API'nin sağlık durumunu kontrol eder.
-/// 
-/// # Returns
-/// 
-/// * `HttpResponse` - JSON formatında sağlık durumu
+/// Canlılık kontrolü
+///
+/// Süreç çalışıyor ve istek kabul ediyorsa 200 döner; hiçbir bağımlılığa dokunmaz.
+/// `/health` bu endpoint'in eski adıdır.
 #[utoipa::path(
     get,
-    path = "/health",
+    path = "/health/live",
     tag = "health",
     responses(
         (status = 200, description = "API çalışıyor", body = ApiResponse)
     )
 )]
-pub async fn health_check() -> Result<HttpResponse> {
+#[tracing::instrument(skip_all)]
+pub async fn liveness() -> ApiResult {
     let response = ApiResponse {
         message: "API çalışıyor".to_string(),
         status: "healthy".to_string(),
@@ -49,6 +70,112 @@ pub async fn health_check() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Hazır olma kontrolü
+///
+/// PostgreSQL erişimini (zaman aşımıyla), migrasyon durumunu ve bağlantı havuzundaki boş kapasiteyi
+/// kontrol eder. Bağımlılıklardan biri sağlıklı değilse 503 döner.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Tüm bağımlılıklar sağlıklı", body = ReadinessResponse),
+        (status = 503, description = "En az bir bağımlılık sağlıklı değil", body = ReadinessResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn readiness(db: web::Data<Database>, config: web::Data<AppConfig>) -> ApiResult {
+    let timeout = std::time::Duration::from_millis(config.server.readiness_timeout_ms);
+    let timeout_message = format!("{} ms içinde yanıt alınamadı", config.server.readiness_timeout_ms);
+
+    // Havuz kapasitesi, kontrol sorguları bağlantı almadan önce ölçülür
+    let (size, idle, max) = db.pool_usage();
+    let available = idle + max.saturating_sub(size);
+    let pool = PoolCheck {
+        status: if available > 0 { HealthStatus::Up } else { HealthStatus::Degraded },
+        size,
+        idle,
+        max,
+        available,
+    };
+
+    let started = std::time::Instant::now();
+    let (ping, migrations) = futures_util::future::join(
+        tokio::time::timeout(timeout, db.ping()),
+        tokio::time::timeout(timeout, db.migration_status()),
+    )
+    .await;
+
+    let (status, error) = match ping {
+        Ok(Ok(())) => (HealthStatus::Up, None),
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "Hazır olma kontrolü: veritabanı hatası");
+            (HealthStatus::Down, Some("Veritabanına ulaşılamıyor".to_string()))
+        }
+        Err(_) => (HealthStatus::Down, Some(timeout_message.clone())),
+    };
+    let database = DatabaseCheck {
+        status,
+        latency_ms: started.elapsed().as_millis() as u64,
+        error,
+    };
+
+    let migrations = match migrations {
+        Ok(Ok(check)) => check,
+        result => {
+            let error = match result {
+                Ok(Err(e)) => {
+                    tracing::warn!(error = %e, "Hazır olma kontrolü: migrasyon durumu okunamadı");
+                    "Migrasyon durumu okunamadı".to_string()
+                }
+                _ => timeout_message,
+            };
+            MigrationCheck {
+                status: HealthStatus::Down,
+                expected: 0,
+                applied: 0,
+                pending: vec![],
+                failed: vec![],
+                error: Some(error),
+            }
+        }
+    };
+
+    let status = database.status.max(migrations.status).max(pool.status);
+    let response = ReadinessResponse {
+        status,
+        checks: ReadinessChecks { database, migrations, pool },
+    };
+    if status == HealthStatus::Up {
+        Ok(HttpResponse::Ok().json(response))
+    } else {
+        Ok(HttpResponse::ServiceUnavailable().json(response))
+    }
+}
+
+/// Prometheus metrikleri
+///
+/// HTTP istek sayıları ve süreleri (route ve durum koduna göre), bağlantı havuzu, maliyetli işlemlerin
+/// süreleri ve kullanıcı/liman sayıları Prometheus metin formatında döner.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Prometheus metin formatında metrikler", body = String, content_type = "text/plain")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn metrics(db: web::Data<Database>, metrics: web::Data<Metrics>) -> ApiResult {
+    let body = metrics
+        .render(&db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Metrikler kodlanamadı: {}", e)))?;
+    Ok(HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(body))
+}
+
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ApiResponse {
     pub message: String,
@@ -59,45 +186,65 @@ pub struct ApiResponse {
 pub struct StatsResponse {
     pub total_users: i64,
     pub status: String,
+    pub users: UserStats,
+    pub ports: PortStats,
 }
 
-// Sağlık kontrolü endpoint'i
-// pub async fn health_check() -> Result<HttpResponse> {
-//     let response = ApiResponse {
-//         message: "API çalışıyor".to_string(),
-//         status: "healthy".to_string(),
-//     };
-//     Ok(HttpResponse::Ok().json(response))
-// }
+/// İstatistiklerde listelenebilecek en fazla aralık sayısı
+const MAX_SIGNUP_PERIODS: i64 = 366;
+
+/// İstatistiklerde listelenebilecek en fazla bölge sayısı
+const MAX_TOP_REGIONS: usize = 100;
 
 // İstatistikler endpoint'i
 #[utoipa::path(
     get,
     path = "/api/v1/stats",
     tag = "stats",
+    params(StatsQuery),
     responses(
-        (status = 200, description = "İstatistikler", body = StatsResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 200, description = "Kullanıcı ve liman istatistikleri", body = StatsResponse),
+        (status = 400, description = "Geçersiz sorgu parametresi", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-pub async fn get_stats(db: web::Data<Database>) -> Result<HttpResponse> {
-    match db.count_users().await {
-        Ok(count) => {
-            let response = StatsResponse {
-                total_users: count,
-                status: "success".to_string(),
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "İstatistikler alınırken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+#[tracing::instrument(skip_all)]
+pub async fn get_stats(
+    query: web::Query<StatsQuery>,
+    db: web::Data<Database>,
+    config: web::Data<AppConfig>,
+) -> ApiResult {
+    let interval = query.interval.unwrap_or_default();
+    let periods = query.periods.unwrap_or(30).clamp(1, MAX_SIGNUP_PERIODS);
+    // Bölgeler heatmap çözünürlüğünden ince olamaz; daha ince hücreler tek tek konumları ele verebilir
+    let resolution = query.resolution.map_or(config.h3.heatmap_resolution, |r| r.min(config.h3.heatmap_resolution));
+    let top = query.top.unwrap_or(10).clamp(1, MAX_TOP_REGIONS);
+
+    let total_users = db.count_users().await?;
+    let (with_location, without_location) = db.count_users_by_location().await?;
+    let signups = db.get_signup_counts(interval, periods).await?;
+    let top_regions = top_regions(
+        db.get_user_heatmap_data(resolution).await?.cells,
+        config.privacy.heatmap_min_count,
+        config.privacy.heatmap_suppression,
+        top,
+    );
+
+    let response = StatsResponse {
+        total_users,
+        status: "success".to_string(),
+        users: UserStats {
+            total: total_users,
+            with_location,
+            without_location,
+            signup_interval: interval,
+            signups,
+            region_resolution: resolution,
+            top_regions,
+        },
+        ports: db.get_port_stats().await?,
+    };
+    Ok(HttpResponse::Ok().json(response))
 }
 
 // Tüm kullanıcıları getirme endpoint'i (GET)
@@ -105,26 +252,20 @@ pub async fn get_stats(db: web::Data<Database>) -> Result<HttpResponse> {
     get,
     path = "/api/v1/users",
     tag = "users",
+    params(IncludeDeletedQuery),
     responses(
         (status = 200, description = "Kullanıcı listesi", body = Vec<UserResponse>),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-pub async fn get_all_users(db: web::Data<Database>) -> Result<HttpResponse> {
-    match db.get_all_users().await {
-        Ok(users) => {
-            let user_responses: Vec<UserResponse> = users.into_iter().map(|u| u.into()).collect();
-            Ok(HttpResponse::Ok().json(user_responses))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Kullanıcılar getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+#[tracing::instrument(skip_all)]
+pub async fn get_all_users(
+    query: web::Query<IncludeDeletedQuery>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let users = db.get_all_users(query.include_deleted).await?;
+    let user_responses: Vec<UserResponse> = users.into_iter().map(|u| u.into()).collect();
+    Ok(HttpResponse::Ok().json(user_responses))
 }
 
 // Kullanıcı oluşturma endpoint'i (POST)
@@ -135,49 +276,27 @@ pub async fn get_all_users(db: web::Data<Database>) -> Result<HttpResponse> {
     request_body = CreateUser,
     responses(
         (status = 201, description = "Kullanıcı oluşturuldu", body = UserResponse),
-        (status = 400, description = "Geçersiz istek", body = ApiResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz istek", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Email adresi zaten kullanımda (email_taken)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn create_user(
-    user_data: web::Json<CreateUser>,
+    user_data: Valid<CreateUser>,
+    principal: web::ReqData<Principal>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    // Email kontrolü
-    match db.get_user_by_email(&user_data.email).await {
-        Ok(Some(_)) => {
-            let response = ApiResponse {
-                message: "Bu email adresi zaten kullanımda".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::BadRequest().json(response));
-        }
-        Ok(None) => {}
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Veritabanı hatası".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::InternalServerError().json(response));
-        }
+) -> ApiResult {
+    // Email kontrolü (eşzamanlı isteklerde unique kısıtı yine 409 döndürür)
+    if db.get_user_by_email(&user_data.email).await?.is_some() {
+        return Err(AppError::conflict("email_taken", "Bu email adresi zaten kullanımda"));
     }
 
-    match db.create_user(user_data.into_inner()).await {
-        Ok(user) => {
-            println!("Yeni kullanıcı oluşturuldu: {} - {}", user.name, user.email);
-            let user_response: UserResponse = user.into();
-            Ok(HttpResponse::Created().json(user_response))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Kullanıcı oluşturulurken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+    let user = db.create_user(user_data.into_inner(), &principal.actor_id()).await?;
+    tracing::info!(user_id = %user.id, "Yeni kullanıcı oluşturuldu");
+    let user_response: UserResponse = user.into();
+    Ok(HttpResponse::Created().json(user_response))
 }
 
 // ID'ye göre kullanıcı getirme endpoint'i (GET)
@@ -186,53 +305,30 @@ pub async fn create_user(
     path = "/api/v1/users/{id}",
     tag = "users",
     params(
-        ("id" = String, Path, description = "Kullanıcı ID'si (UUID)")
+        ("id" = String, Path, description = "Kullanıcı ID'si (UUID)"),
+        IncludeDeletedQuery
     ),
     responses(
         (status = 200, description = "Kullanıcı bulundu", body = UserResponse),
-        (status = 400, description = "Geçersiz ID formatı", body = ApiResponse),
-        (status = 404, description = "Kullanıcı bulunamadı", body = ApiResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn get_user(
     path: web::Path<String>,
+    query: web::Query<IncludeDeletedQuery>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    let user_id_str = path.into_inner();
-    
-    let user_id = match Uuid::parse_str(&user_id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            let response = ApiResponse {
-                message: "Geçersiz kullanıcı ID formatı".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::BadRequest().json(response));
-        }
-    };
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
 
-    match db.get_user_by_id(user_id).await {
-        Ok(Some(user)) => {
-            let user_response: UserResponse = user.into();
-            Ok(HttpResponse::Ok().json(user_response))
-        }
-        Ok(None) => {
-            let response = ApiResponse {
-                message: "Kullanıcı bulunamadı".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::NotFound().json(response))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Kullanıcı getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+    let user = db
+        .get_user_by_id(user_id, query.include_deleted)
+        .await?
+        .ok_or_else(|| AppError::not_found("Kullanıcı bulunamadı"))?;
+    let user_response: UserResponse = user.into();
+    Ok(HttpResponse::Ok().json(user_response))
 }
 
 // Kullanıcı güncelleme endpoint'i (PUT)
@@ -246,73 +342,39 @@ pub async fn get_user(
     request_body = UpdateUser,
     responses(
         (status = 200, description = "Kullanıcı güncellendi", body = UserResponse),
-        (status = 400, description = "Geçersiz ID formatı", body = ApiResponse),
-        (status = 404, description = "Kullanıcı bulunamadı", body = ApiResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Email adresi başka bir kullanıcıda (email_taken)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn update_user(
     path: web::Path<String>,
-    user_data: web::Json<UpdateUser>,
+    user_data: Valid<UpdateUser>,
+    principal: web::ReqData<Principal>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    let user_id_str = path.into_inner();
-    
-    let user_id = match Uuid::parse_str(&user_id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            let response = ApiResponse {
-                message: "Geçersiz kullanıcı ID formatı".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::BadRequest().json(response));
-        }
-    };
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
 
     // Email değişikliği varsa, başka kullanıcı tarafından kullanılmıyor mu kontrol et
     if let Some(ref email) = user_data.email {
-        match db.get_user_by_email(email).await {
-            Ok(Some(existing_user)) if existing_user.id != user_id => {
-                let response = ApiResponse {
-                    message: "Bu email adresi başka bir kullanıcı tarafından kullanılıyor".to_string(),
-                    status: "error".to_string(),
-                };
-                return Ok(HttpResponse::BadRequest().json(response));
-            }
-            Ok(_) => {}
-            Err(e) => {
-                eprintln!("Database error: {}", e);
-                let response = ApiResponse {
-                    message: "Veritabanı hatası".to_string(),
-                    status: "error".to_string(),
-                };
-                return Ok(HttpResponse::InternalServerError().json(response));
-            }
+        if db.get_user_by_email(email).await?.is_some_and(|existing| existing.id != user_id) {
+            return Err(AppError::conflict(
+                "email_taken",
+                "Bu email adresi başka bir kullanıcı tarafından kullanılıyor",
+            ));
         }
     }
 
-    match db.update_user(user_id, user_data.into_inner()).await {
-        Ok(Some(user)) => {
-            println!("Kullanıcı güncellendi: {} - {}", user.name, user.email);
-            let user_response: UserResponse = user.into();
-            Ok(HttpResponse::Ok().json(user_response))
-        }
-        Ok(None) => {
-            let response = ApiResponse {
-                message: "Kullanıcı bulunamadı".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::NotFound().json(response))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Kullanıcı güncellenirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+    let user = db
+        .update_user(user_id, user_data.into_inner(), &principal.actor_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Kullanıcı bulunamadı"))?;
+    tracing::info!(user_id = %user.id, "Kullanıcı güncellendi");
+    let user_response: UserResponse = user.into();
+    Ok(HttpResponse::Ok().json(user_response))
 }
 
 // Kullanıcı silme endpoint'i (DELETE)
@@ -325,55 +387,194 @@ pub async fn update_user(
     ),
     responses(
         (status = 200, description = "Kullanıcı silindi", body = ApiResponse),
-        (status = 400, description = "Geçersiz ID formatı", body = ApiResponse),
-        (status = 404, description = "Kullanıcı bulunamadı", body = ApiResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn delete_user(
     path: web::Path<String>,
+    principal: web::ReqData<Principal>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    let user_id_str = path.into_inner();
-    
-    let user_id = match Uuid::parse_str(&user_id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            let response = ApiResponse {
-                message: "Geçersiz kullanıcı ID formatı".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::BadRequest().json(response));
-        }
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
+
+    if !db.delete_user(user_id, &principal.actor_id()).await? {
+        return Err(AppError::not_found("Kullanıcı bulunamadı"));
+    }
+
+    tracing::info!(%user_id, "Kullanıcı silindi");
+    let response = ApiResponse {
+        message: "Kullanıcı başarıyla silindi".to_string(),
+        status: "success".to_string(),
     };
+    Ok(HttpResponse::Ok().json(response))
+}
 
-    match db.delete_user(user_id).await {
-        Ok(true) => {
-            println!("Kullanıcı silindi: {}", user_id);
-            let response = ApiResponse {
-                message: "Kullanıcı başarıyla silindi".to_string(),
-                status: "success".to_string(),
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        Ok(false) => {
-            let response = ApiResponse {
-                message: "Kullanıcı bulunamadı".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::NotFound().json(response))
+// Silinmiş kullanıcıyı geri yükleme endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/restore",
+    tag = "users",
+    params(
+        ("id" = String, Path, description = "Kullanıcı ID'si (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Kullanıcı geri yüklendi", body = UserResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Silinmiş kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Email adresi başka bir kullanıcıda (email_taken)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn restore_user(
+    path: web::Path<String>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
+
+    let user = db
+        .restore_user(user_id, &principal.actor_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Silinmiş kullanıcı bulunamadı"))?;
+    tracing::info!(user_id = %user.id, "Kullanıcı geri yüklendi");
+    let user_response: UserResponse = user.into();
+    Ok(HttpResponse::Ok().json(user_response))
+}
+
+// Toplu kullanıcı içe aktarma endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/import",
+    tag = "users",
+    params(ImportUsersQuery),
+    request_body(
+        content = String,
+        description = "CSV (başlık satırı: name,email,latitude,longitude) veya NDJSON (satır başına bir CreateUser)",
+        content_type = "text/csv"
+    ),
+    responses(
+        (status = 200, description = "Satır bazında içe aktarma raporu", body = ImportReport),
+        (status = 400, description = "Format belirlenemedi veya CSV başlığı geçersiz", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası; bazı gruplar eklendiyse `aborted` alanlı kısmi rapor döner", body = ImportReport)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn import_users(
+    req: HttpRequest,
+    mut payload: web::Payload,
+    query: web::Query<ImportUsersQuery>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let format = match query.format {
+        Some(format) => format,
+        None => bulk_format_from_content_type(&req)?,
+    };
+
+    let actor = principal.actor_id();
+    let mut importer = UserImporter::new(&db, &actor, format, query.dry_run);
+    let mut splitter = LineSplitter::default();
+    let mut row = 0u64;
+
+    // Gövde belleğe alınmadan satır satır işlenir
+    let streamed: Result<(), AppError> = async {
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.map_err(|e| AppError::bad_request("malformed_body", e.to_string()))?;
+            for line in splitter.push(&chunk) {
+                row += 1;
+                importer.push_line(row, line).await?;
+            }
         }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Kullanıcı silinirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
+        if let Some(line) = splitter.finish() {
+            row += 1;
+            importer.push_line(row, line).await?;
         }
+        importer.flush().await
+    }
+    .await;
+
+    // Önceki gruplar eklenmişken oluşan hatada istemci hangi satırların eklendiğini rapordan öğrenir
+    if let Err(error) = streamed {
+        let status = error.status_code();
+        let report = importer.abort(error)?;
+        return Ok(HttpResponse::build(status).json(report));
+    }
+
+    let report = importer.finish().await?;
+    tracing::info!(
+        dry_run = report.dry_run,
+        total_rows = report.total_rows,
+        imported = report.imported,
+        failed = report.failed,
+        "Toplu kullanıcı içe aktarma tamamlandı"
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Content-Type başlığından toplu aktarma formatını çıkarır
+fn bulk_format_from_content_type(req: &HttpRequest) -> Result<BulkFormat, AppError> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "text/csv" => Ok(BulkFormat::Csv),
+        "application/x-ndjson" | "application/ndjson" | "application/jsonl" => Ok(BulkFormat::Ndjson),
+        _ => Err(AppError::bad_request(
+            "unsupported_format",
+            "Format belirlenemedi: ?format=csv|ndjson verin veya Content-Type text/csv ya da application/x-ndjson olmalı",
+        )),
     }
 }
 
+// Kullanıcıları dışa aktarma endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/export",
+    tag = "users",
+    params(ExportUsersQuery),
+    responses(
+        (status = 200, description = "Kullanıcılar (CSV veya NDJSON, akış halinde)", body = String, content_type = "text/csv"),
+        (status = 400, description = "Geçersiz sorgu parametresi", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn export_users(
+    query: web::Query<ExportUsersQuery>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let format = query.format.unwrap_or(BulkFormat::Csv);
+    let include_deleted = query.include_deleted;
+    let db = db.into_inner();
+
+    // Kullanıcılar id sırasıyla sayfa sayfa okunur ve her sayfa ayrı bir parça olarak gönderilir
+    let pages = stream::try_unfold((None, true, false), move |(after_id, first, done)| {
+        let db = db.clone();
+        async move {
+            if done {
+                return Ok(None);
+            }
+            let users = db.get_users_page(after_id, EXPORT_PAGE_SIZE, include_deleted).await?;
+            let done = (users.len() as i64) < EXPORT_PAGE_SIZE;
+            let chunk = encode_users(&users, format, first)?;
+            Ok::<_, AppError>(Some((chunk, (users.last().map(|u| u.id), false, done))))
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"users.{}\"", format.extension()),
+        ))
+        .streaming(pages))
+}
+
 // ====== PORT HANDLERS ======
 
 // Tüm limanları getirme endpoint'i (GET)
@@ -381,26 +582,120 @@ pub async fn delete_user(
     get,
     path = "/api/v1/ports",
     tag = "ports",
+    params(IncludeDeletedQuery),
     responses(
         (status = 200, description = "Liman listesi", body = Vec<PortResponse>),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-pub async fn get_all_ports(db: web::Data<Database>) -> Result<HttpResponse> {
-    match db.get_all_ports().await {
-        Ok(ports) => {
-            let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
-            Ok(HttpResponse::Ok().json(port_responses))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Limanlar getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
+#[tracing::instrument(skip_all)]
+pub async fn get_all_ports(
+    query: web::Query<IncludeDeletedQuery>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let ports = db.get_all_ports(query.include_deleted).await?;
+    let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
+    Ok(HttpResponse::Ok().json(port_responses))
+}
+
+// ID'ye göre liman getirme endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/ports/{id}",
+    tag = "ports",
+    params(
+        ("id" = String, Path, description = "Liman ID'si (UUID)"),
+        IncludeDeletedQuery
+    ),
+    responses(
+        (status = 200, description = "Liman bulundu", body = PortResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Liman bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_port(
+    path: web::Path<String>,
+    query: web::Query<IncludeDeletedQuery>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let port_id = parse_id(&path.into_inner(), "liman")?;
+
+    let port = db
+        .get_port_by_id(port_id, query.include_deleted)
+        .await?
+        .ok_or_else(|| AppError::not_found("Liman bulunamadı"))?;
+    let port_response: PortResponse = port.into();
+    Ok(HttpResponse::Ok().json(port_response))
+}
+
+// Liman silme endpoint'i (DELETE)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/ports/{id}",
+    tag = "ports",
+    params(
+        ("id" = String, Path, description = "Liman ID'si (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Liman silindi", body = ApiResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Liman bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_port(
+    path: web::Path<String>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let port_id = parse_id(&path.into_inner(), "liman")?;
+
+    if !db.delete_port(port_id, &principal.actor_id()).await? {
+        return Err(AppError::not_found("Liman bulunamadı"));
     }
+
+    tracing::info!(%port_id, "Liman silindi");
+    let response = ApiResponse {
+        message: "Liman başarıyla silindi".to_string(),
+        status: "success".to_string(),
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// Silinmiş limanı geri yükleme endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/ports/{id}/restore",
+    tag = "ports",
+    params(
+        ("id" = String, Path, description = "Liman ID'si (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Liman geri yüklendi", body = PortResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Silinmiş liman bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Liman kodu başka bir limanda (port_code_taken)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn restore_port(
+    path: web::Path<String>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let port_id = parse_id(&path.into_inner(), "liman")?;
+
+    let port = db
+        .restore_port(port_id, &principal.actor_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Silinmiş liman bulunamadı"))?;
+    tracing::info!(port_id = %port.id, port_code = %port.code, "Liman geri yüklendi");
+    let port_response: PortResponse = port.into();
+    Ok(HttpResponse::Ok().json(port_response))
 }
 
 // Liman oluşturma endpoint'i (POST)
@@ -411,49 +706,27 @@ pub async fn get_all_ports(db: web::Data<Database>) -> Result<HttpResponse> {
     request_body = CreatePort,
     responses(
         (status = 201, description = "Liman oluşturuldu", body = PortResponse),
-        (status = 400, description = "Geçersiz istek", body = ApiResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz istek", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Liman kodu zaten kullanımda (port_code_taken)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn create_port(
-    port_data: web::Json<CreatePort>,
+    port_data: Valid<CreatePort>,
+    principal: web::ReqData<Principal>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    // Kod kontrolü
-    match db.get_port_by_code(&port_data.code).await {
-        Ok(Some(_)) => {
-            let response = ApiResponse {
-                message: "Bu liman kodu zaten kullanımda".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::BadRequest().json(response));
-        }
-        Ok(None) => {}
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Veritabanı hatası".to_string(),
-                status: "error".to_string(),
-            };
-            return Ok(HttpResponse::InternalServerError().json(response));
-        }
+) -> ApiResult {
+    // Kod kontrolü (eşzamanlı isteklerde unique kısıtı yine 409 döndürür)
+    if db.get_port_by_code(&port_data.code).await?.is_some() {
+        return Err(AppError::conflict("port_code_taken", "Bu liman kodu zaten kullanımda"));
     }
 
-    match db.create_port(port_data.into_inner()).await {
-        Ok(port) => {
-            println!("Yeni liman oluşturuldu: {} - {}", port.name, port.code);
-            let port_response: PortResponse = port.into();
-            Ok(HttpResponse::Created().json(port_response))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Liman oluşturulurken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+    let port = db.create_port(port_data.into_inner(), &principal.actor_id()).await?;
+    tracing::info!(port_id = %port.id, port_code = %port.code, "Yeni liman oluşturuldu");
+    let port_response: PortResponse = port.into();
+    Ok(HttpResponse::Created().json(port_response))
 }
 
 // En yakın liman bulma endpoint'i (POST)
@@ -464,27 +737,29 @@ pub async fn create_port(
     request_body = FindNearestPortRequest,
     responses(
         (status = 200, description = "Yakındaki limanlar", body = Vec<PortResponse>),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn find_nearest_port(
-    request: web::Json<FindNearestPortRequest>,
+    request: Valid<FindNearestPortRequest>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    match db.find_nearest_port(request.latitude, request.longitude, None).await {
-        Ok(ports) => {
-            let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
-            Ok(HttpResponse::Ok().json(port_responses))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Yakındaki limanlar aranırken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+    config: web::Data<AppConfig>,
+    metrics: web::Data<Metrics>,
+) -> ApiResult {
+    let ports = {
+        let _timer = metrics.time_operation("find_nearest_port");
+        db.find_nearest_port(
+            request.latitude,
+            request.longitude,
+            config.h3.nearest_port_max_distance_km,
+            config.h3.nearest_port_k_ring,
+        )
+        .await?
+    };
+    let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
+    Ok(HttpResponse::Ok().json(port_responses))
 }
 
 // Ülkeye göre limanları getirme endpoint'i (GET)
@@ -497,29 +772,19 @@ pub async fn find_nearest_port(
     ),
     responses(
         (status = 200, description = "Liman listesi", body = Vec<PortResponse>),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn get_ports_by_country(
     path: web::Path<String>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
+) -> ApiResult {
     let country = path.into_inner();
-    
-    match db.get_ports_by_country(&country).await {
-        Ok(ports) => {
-            let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
-            Ok(HttpResponse::Ok().json(port_responses))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Limanlar getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
-    }
+
+    let ports = db.get_ports_by_country(&country).await?;
+    let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
+    Ok(HttpResponse::Ok().json(port_responses))
 }
 
 // Liman tipine göre limanları getirme endpoint'i (GET)
@@ -528,72 +793,625 @@ pub async fn get_ports_by_country(
     path = "/api/v1/ports/type/{port_type}",
     tag = "ports",
     params(
-        ("port_type" = String, Path, description = "Liman tipi (container, cruise, cargo, fishing)")
+        ("port_type" = String, Path, description = "Liman tipi (container, cruise, cargo, fishing, ferry)")
     ),
     responses(
         (status = 200, description = "Liman listesi", body = Vec<PortResponse>),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn get_ports_by_type(
     path: web::Path<String>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
+) -> ApiResult {
     let port_type = path.into_inner();
-    
-    match db.get_ports_by_type(&port_type).await {
-        Ok(ports) => {
-            let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
-            Ok(HttpResponse::Ok().json(port_responses))
-        }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Limanlar getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
-        }
+
+    let ports = db.get_ports_by_type(&port_type).await?;
+    let port_responses: Vec<PortResponse> = ports.into_iter().map(|p| p.into()).collect();
+    Ok(HttpResponse::Ok().json(port_responses))
+}
+
+// Liman etki alanlarını getirme endpoint'i (GET)
+//
+// Rapor önbellekten döner; önbellekte yoksa veya süresi dolduysa arka planda hesaplanır ve 202 ile işin
+// durumu döner, istemci aynı isteği tekrarlar.
+#[utoipa::path(
+    get,
+    path = "/api/v1/ports/catchments",
+    tag = "ports",
+    params(CatchmentQuery),
+    responses(
+        (status = 200, description = "Limanlara göre kullanıcı sayıları, medyan uzaklıklar ve etki alanları", body = CatchmentReport),
+        (status = 202, description = "Rapor hesaplanıyor; Retry-After süresi sonra tekrar deneyin", body = CatchmentJob),
+        (status = 400, description = "Geçersiz liman tipi", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["stats:read"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_port_catchments(
+    req: HttpRequest,
+    query: web::Query<CatchmentQuery>,
+    db: web::Data<Database>,
+    config: web::Data<AppConfig>,
+    cache: web::Data<CatchmentCache>,
+) -> ApiResult {
+    let query = query.into_inner();
+    check_port_type(query.port_type.as_deref())?;
+    // Konfigürasyondaki çözünürlükten ince etki alanları tek tek konumları çizer ve her çözünürlük ayrı bir
+    // tam tablo hesaplaması başlatır
+    let resolution = query.resolution.map_or(config.h3.catchment_resolution, |r| r.min(config.h3.catchment_resolution));
+
+    match CatchmentCache::get_or_start(cache, db, query.port_type, resolution, query.refresh.unwrap_or(false)) {
+        CatchmentLookup::Ready(report) => Ok(HttpResponse::Ok().json(report.as_ref())),
+        CatchmentLookup::Pending(job) => Ok(HttpResponse::Accepted()
+            .insert_header((header::LOCATION, req.uri().to_string()))
+            .insert_header((header::RETRY_AFTER, CATCHMENT_RETRY_AFTER_SECS))
+            .json(job)),
     }
 }
 
-// H3 tabanlı kullanıcı heatmap verilerini getirme endpoint'i (GET)
+/// Hesaplanan etki alanı raporu için istemcinin bekleyeceği süre (saniye)
+const CATCHMENT_RETRY_AFTER_SECS: u64 = 2;
+
+/// Kapsama boşluğu analizinde döndürülebilecek en fazla hücre
+const MAX_COVERAGE_GAP_CELLS: usize = 1000;
+
+// Limanların iyi hizmet veremediği kalabalık bölgeleri getirme endpoint'i (GET)
 #[utoipa::path(
     get,
-    path = "/api/v1/users/heatmap",
-    tag = "users",
-    params(
-        ("resolution" = Option<u8>, Query, description = "H3 çözünürlüğü (varsayılan: 8)")
+    path = "/api/v1/ports/coverage-gaps",
+    tag = "ports",
+    params(CoverageGapQuery),
+    responses(
+        (status = 200, description = "En yakın limanı eşikten uzak olan hücreler (kalabalık önce)", content(
+            ("application/json" = CoverageGapReport),
+            ("application/geo+json" = CoverageGapCollection)
+        )),
+        (status = 400, description = "Geçersiz liman tipi veya mesafe", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     ),
+    security(("api_key" = ["stats:read"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_coverage_gaps(
+    query: web::Query<CoverageGapQuery>,
+    db: web::Data<Database>,
+    config: web::Data<AppConfig>,
+) -> ApiResult {
+    let query = query.into_inner();
+    check_port_type(query.port_type.as_deref())?;
+    let max_distance_km = query.max_distance_km.unwrap_or(config.h3.nearest_port_max_distance_km);
+    if !max_distance_km.is_finite() || max_distance_km <= 0.0 {
+        return Err(AppError::bad_request("invalid_distance", "max_distance_km pozitif bir sayı olmalı"));
+    }
+    // Hücreler heatmap çözünürlüğünden ince olamaz ve eşiğin altındakiler yalnızca toplam olarak raporlanır
+    let resolution = query.resolution.unwrap_or(config.h3.catchment_resolution).min(config.h3.heatmap_resolution);
+    let limit = query.limit.unwrap_or(100).clamp(1, MAX_COVERAGE_GAP_CELLS);
+    let min_count = config.privacy.heatmap_min_count;
+
+    let (ports, users) = catchment::load_inputs(&db, query.port_type.as_deref()).await?;
+    let located_users = users.len() as i64;
+    let (mut gaps, suppressed_users) = web::block(move || catchment::coverage_gaps(&ports, &users, resolution, max_distance_km, min_count))
+        .await
+        .map_err(|e| AppError::Internal(format!("Kapsama analizi çalıştırılamadı: {}", e)))?;
+    let total_cells = gaps.len();
+    let underserved_users = gaps.iter().map(|gap| gap.user_count).sum();
+    gaps.truncate(limit);
+
+    if query.format.unwrap_or_default() == CoverageFormat::Geojson {
+        let features = gaps
+            .into_iter()
+            .map(|gap| CoverageGapFeature {
+                kind: "Feature".to_string(),
+                geometry: GeoJsonMultiPolygon::new(cells_to_multipolygon(std::slice::from_ref(&gap.h3_index)).unwrap_or_default()),
+                properties: gap,
+            })
+            .collect();
+        let collection = CoverageGapCollection { kind: "FeatureCollection".to_string(), features };
+        return Ok(HttpResponse::Ok().content_type("application/geo+json").json(collection));
+    }
+
+    Ok(HttpResponse::Ok().json(CoverageGapReport {
+        resolution,
+        max_distance_km,
+        port_type: query.port_type,
+        located_users,
+        underserved_users,
+        suppressed_users,
+        total_cells,
+        cells: gaps,
+    }))
+}
+
+// Sorgudaki liman tipinin geçerli olduğunu doğrular
+fn check_port_type(port_type: Option<&str>) -> Result<(), AppError> {
+    match port_type {
+        Some(port_type) if !PORT_TYPES.contains(&port_type) => Err(AppError::bad_request(
+            "invalid_port_type",
+            format!("Liman tipi şunlardan biri olmalı: {}", PORT_TYPES.join(", ")),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// H3 tabanlı heatmap verilerini getirme endpoint'i (GET)
+//
+// Varsayılan katman kullanıcılardır. Kimliği doğrulanmamış veya `heatmap:read` kapsamı olmayan çağıranlar
+// kullanıcı katmanını her zaman gizlilik modunda, konfigürasyondaki eşik ve epsilon ile ve zaman aralığı olmadan
+// alır. Liman katmanı
+// `ports:read` kapsamı ister ve kişisel veri içermediği için gizlilik modu uygulanmaz.
+#[utoipa::path(
+    get,
+    path = "/api/v1/heatmap",
+    tag = "users",
+    params(HeatmapQuery),
     responses(
         (status = 200, description = "Heatmap verileri", body = H3HeatmapResponse),
-        (status = 500, description = "Sunucu hatası", body = ApiResponse)
+        (status = 400, description = "Geçersiz epsilon, metrik, alan veya zaman aralığı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Liman katmanı veya kullanıcı katmanında zaman aralığı için kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Liman katmanı için ports:read, kullanıcı katmanında zaman aralığı için heatmap:read kapsamı gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Gizlilik bütçesi tükendi", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
+#[tracing::instrument(skip_all)]
 pub async fn get_user_heatmap(
-    query: web::Query<std::collections::HashMap<String, String>>,
+    req: HttpRequest,
+    query: web::Query<HeatmapQuery>,
+    principal: Option<web::ReqData<Principal>>,
     db: web::Data<Database>,
-) -> Result<HttpResponse> {
-    // Çözünürlüğü sorgu parametresinden al, varsayılan olarak 8 kullan
-    let resolution = query
-        .get("resolution")
-        .and_then(|r| r.parse::<u8>().ok())
-        .unwrap_or(8);
-    
-    // 0-15 aralığında bir çözünürlük olduğundan emin ol
-    let resolution = resolution.clamp(0, 15);
-
-    match db.get_user_heatmap_data(resolution).await {
-        Ok(heatmap_data) => {
-            Ok(HttpResponse::Ok().json(heatmap_data))
+    config: web::Data<AppConfig>,
+    budget: web::Data<PrivacyBudget>,
+    metrics: web::Data<Metrics>,
+) -> ApiResult {
+    let query = query.into_inner();
+
+    // 0-15 aralığında bir çözünürlük olduğundan emin ol, verilmemişse konfigürasyondaki varsayılanı kullan
+    let resolution = query.resolution.unwrap_or(config.h3.heatmap_resolution).clamp(0, 15);
+    let layer = query.layer.unwrap_or_default();
+    let metric = query.metric.unwrap_or_default();
+    let field = heatmap_field(layer, metric, query.field.as_deref())?;
+    let window = check_time_range(TimeRange { from: query.from, to: query.to }, "from", "to")?;
+
+    if layer == HeatmapLayer::Ports {
+        require_layer_scope(principal.as_deref(), Scope::PortsRead, "Liman katmanı")?;
+        let heatmap_data = {
+            let _timer = metrics.time_operation("get_port_heatmap_data");
+            db.get_heatmap_data(layer, metric, field, resolution, window).await?
+        };
+        return Ok(HttpResponse::Ok().json(heatmap_data));
+    }
+
+    // Zaman pencereleri yalnızca `heatmap:read` ile daraltılabilir; aksi halde örtüşen pencerelerin farkı eşiğin
+    // altındaki tek tek kayıtları ortaya çıkarır
+    let authorized = principal.as_ref().is_some_and(|p| p.has_scope(Scope::HeatmapRead));
+    if !authorized && (window.from.is_some() || window.to.is_some()) {
+        require_layer_scope(principal.as_deref(), Scope::HeatmapRead, "Kullanıcı katmanında zaman aralığı")?;
+    }
+    let mut heatmap_data = {
+        let _timer = metrics.time_operation("get_user_heatmap_data");
+        db.get_heatmap_data(layer, metric, field, resolution, window).await?
+    };
+    if authorized && !query.privacy.unwrap_or(false) {
+        return Ok(HttpResponse::Ok().json(heatmap_data));
+    }
+
+    // Yalnızca yetkili çağıranlar gizleme modunu ve epsilon'u seçebilir
+    let (suppression, epsilon) = if authorized {
+        (
+            query.suppression.unwrap_or(config.privacy.heatmap_suppression),
+            query.epsilon.or(config.privacy.heatmap_noise_epsilon),
+        )
+    } else {
+        (config.privacy.heatmap_suppression, config.privacy.heatmap_noise_epsilon)
+    };
+    if let Some(epsilon) = epsilon {
+        if !epsilon.is_finite() || epsilon <= 0.0 {
+            return Err(AppError::bad_request("invalid_epsilon", "epsilon pozitif bir sayı olmalı"));
         }
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            let response = ApiResponse {
-                message: "Heatmap verileri getirilirken hata oluştu".to_string(),
-                status: "error".to_string(),
-            };
-            Ok(HttpResponse::InternalServerError().json(response))
+    }
+
+    // Gürültü ekleniyorsa çağıranın bütçesinden düş (anonim çağıranlar IP adresine göre izlenir)
+    let budget_remaining = match epsilon {
+        Some(epsilon) => {
+            let caller = caller_key(principal.as_deref(), &req.connection_info(), config.server.trust_proxy_headers);
+            let remaining = budget.try_spend(&caller, epsilon).map_err(|remaining| AppError::TooManyRequests {
+                code: "privacy_budget_exhausted",
+                detail: format!(
+                    "Gizlilik bütçesi yetersiz: kalan {:.2}, istenen {:.2} ({} saat içinde yenilenir)",
+                    remaining, epsilon, BUDGET_WINDOW_HOURS
+                ),
+            })?;
+            Some(remaining)
         }
+        None => None,
+    };
+
+    let (cells, suppressed_users) = anonymize_cells(
+        std::mem::take(&mut heatmap_data.cells),
+        config.privacy.heatmap_min_count,
+        suppression,
+        epsilon,
+        &mut rand::thread_rng(),
+    );
+    heatmap_data.total_count = cells.iter().map(|cell| cell.count).sum();
+    heatmap_data.total_value = heatmap_data.total_count as f64;
+    heatmap_data.total_users = Some(heatmap_data.total_count);
+    heatmap_data.cells = cells;
+    heatmap_data.privacy = Some(HeatmapPrivacy {
+        min_count: config.privacy.heatmap_min_count,
+        suppression,
+        suppressed_users,
+        epsilon,
+        budget_remaining,
+    });
+
+    Ok(HttpResponse::Ok().json(heatmap_data))
+}
+
+// İki zaman penceresindeki heatmap'leri hücre bazında karşılaştırma endpoint'i (GET)
+//
+// Her iki pencere de `[başlangıç, bitiş)` aralığında oluşturulan kayıtları sayar. Karşılaştırma ham sayılar
+// üzerinden yapıldığından kullanıcı katmanı `heatmap:read`, liman katmanı `ports:read` kapsamı ister.
+#[utoipa::path(
+    get,
+    path = "/api/v1/heatmap/compare",
+    tag = "users",
+    params(HeatmapCompareQuery),
+    responses(
+        (status = 200, description = "Hücre bazında pencere karşılaştırması", body = HeatmapComparison),
+        (status = 400, description = "Geçersiz zaman aralığı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Katman için gereken kapsam eksik", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["heatmap:read", "ports:read"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_heatmap_comparison(
+    query: web::Query<HeatmapCompareQuery>,
+    principal: Option<web::ReqData<Principal>>,
+    db: web::Data<Database>,
+    config: web::Data<AppConfig>,
+    metrics: web::Data<Metrics>,
+) -> ApiResult {
+    let query = query.into_inner();
+    let resolution = query.resolution.unwrap_or(config.h3.heatmap_resolution).clamp(0, 15);
+    let layer = query.layer.unwrap_or_default();
+    match layer {
+        HeatmapLayer::Users => require_layer_scope(principal.as_deref(), Scope::HeatmapRead, "Kullanıcı katmanı")?,
+        HeatmapLayer::Ports => require_layer_scope(principal.as_deref(), Scope::PortsRead, "Liman katmanı")?,
+    }
+
+    let baseline_window =
+        check_time_range(TimeRange { from: Some(query.baseline_from), to: Some(query.baseline_to) }, "baseline_from", "baseline_to")?;
+    let window = check_time_range(TimeRange { from: Some(query.from), to: Some(query.to) }, "from", "to")?;
+
+    let (baseline, current) = {
+        let _timer = metrics.time_operation("get_heatmap_comparison");
+        let baseline = db.get_heatmap_data(layer, HeatmapMetric::Count, None, resolution, baseline_window).await?;
+        let current = db.get_heatmap_data(layer, HeatmapMetric::Count, None, resolution, window).await?;
+        (baseline, current)
+    };
+
+    Ok(HttpResponse::Ok().json(HeatmapComparison {
+        layer,
+        resolution,
+        baseline_window,
+        window,
+        baseline_total: baseline.total_count,
+        total: current.total_count,
+        cells: heatmap::compare(&baseline.cells, &current.cells),
+    }))
+}
+
+// Heatmap katmanı için gereken kapsamı doğrular
+fn require_layer_scope(principal: Option<&Principal>, scope: Scope, layer: &str) -> Result<(), AppError> {
+    match principal.map(|p| p.has_scope(scope)) {
+        Some(true) => Ok(()),
+        Some(false) => Err(AppError::Forbidden(format!("{} için '{}' kapsamı gerekli", layer, scope))),
+        None => Err(AppError::Unauthorized(format!("{} için kimlik doğrulaması gerekli", layer))),
+    }
+}
+
+// İki sınır da verilmişse başlangıcın bitişten önce olduğunu doğrular
+fn check_time_range(range: TimeRange, from_param: &str, to_param: &str) -> Result<TimeRange, AppError> {
+    match (range.from, range.to) {
+        (Some(from), Some(to)) if from >= to => Err(AppError::bad_request(
+            "invalid_time_range",
+            format!("{} değeri {} değerinden önce olmalı", from_param, to_param),
+        )),
+        _ => Ok(range),
+    }
+}
+
+// Metriğin hesaplanacağı alanı seçer: `count` alan almaz, `sum`/`avg` katmanın sayısal alanlarından birini ister
+fn heatmap_field(layer: HeatmapLayer, metric: HeatmapMetric, field: Option<&str>) -> Result<Option<&'static str>, AppError> {
+    let fields = layer.value_fields();
+    match (metric, field) {
+        (HeatmapMetric::Count, None) => Ok(None),
+        (HeatmapMetric::Count, Some(_)) => Err(AppError::bad_request("invalid_metric", "count metriği alan almaz")),
+        (_, None) => fields.first().copied().map(Some).ok_or_else(|| {
+            AppError::bad_request("invalid_metric", format!("{} katmanında sayısal alan yok; yalnızca count kullanılabilir", layer.as_str()))
+        }),
+        (_, Some(field)) => fields.iter().find(|f| **f == field).copied().map(Some).ok_or_else(|| {
+            AppError::bad_request(
+                "invalid_metric",
+                format!("{} katmanında '{}' alanı yok (kullanılabilir alanlar: {})", layer.as_str(), field, fields.join(", ")),
+            )
+        }),
+    }
+}
+
+// ====== API KEY HANDLERS ======
+
+// Tüm API anahtarlarını getirme endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    responses(
+        (status = 200, description = "API anahtarı listesi", body = Vec<ApiKeyResponse>),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_all_api_keys(db: web::Data<Database>) -> ApiResult {
+    let api_keys = db.get_all_api_keys().await?;
+    let api_key_responses: Vec<ApiKeyResponse> = api_keys.into_iter().map(|k| k.into()).collect();
+    Ok(HttpResponse::Ok().json(api_key_responses))
+}
+
+// API anahtarı oluşturma endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    request_body = CreateApiKey,
+    responses(
+        (status = 201, description = "API anahtarı oluşturuldu; tam anahtar yalnızca bu yanıtta döner", body = CreatedApiKeyResponse),
+        (status = 400, description = "Geçersiz istek", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn create_api_key(
+    key_data: Valid<CreateApiKey>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let (key, prefix) = generate_api_key();
+
+    let api_key = db.create_api_key(key_data.into_inner(), &prefix, &hash_api_key(&key)).await?;
+    tracing::info!(api_key_id = %api_key.id, prefix = %api_key.prefix, actor = %principal.actor_id(), "Yeni API anahtarı oluşturuldu");
+    let response = CreatedApiKeyResponse {
+        key,
+        api_key: api_key.into(),
+    };
+    Ok(HttpResponse::Created().json(response))
+}
+
+// API anahtarı iptal etme endpoint'i (DELETE)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/api-keys/{id}",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "API anahtarı ID'si (UUID)")
+    ),
+    responses(
+        (status = 200, description = "API anahtarı iptal edildi", body = ApiResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Aktif API anahtarı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn revoke_api_key(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let key_id = parse_id(&path.into_inner(), "API anahtarı")?;
+
+    if !db.revoke_api_key(key_id).await? {
+        return Err(AppError::not_found("Aktif API anahtarı bulunamadı"));
     }
+
+    tracing::info!(api_key_id = %key_id, "API anahtarı iptal edildi");
+    let response = ApiResponse {
+        message: "API anahtarı başarıyla iptal edildi".to_string(),
+        status: "success".to_string(),
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// ====== GDPR HANDLERS ======
+
+// Kullanıcı hakkında tutulan tüm verileri getirme endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/subject-access",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Kullanıcı ID'si (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Kullanıcı hakkında tutulan tüm veriler", body = SubjectAccessExport),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_user_subject_access(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
+
+    let user = db
+        .get_user_by_id(user_id, true)
+        .await?
+        .ok_or_else(|| AppError::not_found("Kullanıcı bulunamadı"))?;
+    let audit_query = AuditLogQuery {
+        entity_type: Some(AuditEntity::User),
+        entity_id: Some(user_id),
+        ..Default::default()
+    };
+    let audit_log = db.get_audit_log(&audit_query, i64::MAX).await?;
+
+    let export = SubjectAccessExport {
+        generated_at: Utc::now(),
+        user: user.into(),
+        audit_log,
+    };
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"user-{}.json\"", user_id),
+        ))
+        .json(export))
+}
+
+// Kullanıcının kişisel verilerini silme endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/erasure",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Kullanıcı ID'si (UUID)")
+    ),
+    request_body = EraseUserRequest,
+    responses(
+        (status = 200, description = "Kişisel veriler silindi", body = UserResponse),
+        (status = 400, description = "Geçersiz ID formatı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Kullanıcı bulunamadı", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn erase_user(
+    path: web::Path<String>,
+    request: Valid<EraseUserRequest>,
+    principal: web::ReqData<Principal>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let user_id = parse_id(&path.into_inner(), "kullanıcı")?;
+    let resolution = request.resolution.unwrap_or(DEFAULT_ERASURE_RESOLUTION);
+
+    let user = db
+        .erase_user(user_id, request.location, resolution, &principal.actor_id())
+        .await?
+        .ok_or_else(|| AppError::not_found("Kullanıcı bulunamadı"))?;
+    tracing::info!(%user_id, actor = %principal.actor_id(), "Kullanıcının kişisel verileri silindi");
+    let user_response: UserResponse = user.into();
+    Ok(HttpResponse::Ok().json(user_response))
+}
+
+// ====== AUDIT LOG HANDLERS ======
+
+/// Sorgu başına döndürülecek en fazla denetim kaydı
+const MAX_AUDIT_LOG_LIMIT: i64 = 1000;
+
+// Denetim kayıtlarını sorgulama endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    tag = "admin",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Denetim kayıtları (en yeni önce)", body = Vec<AuditLogEntry>),
+        (status = 400, description = "Geçersiz sorgu parametresi", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Sunucu hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_audit_log(
+    query: web::Query<AuditLogQuery>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let limit = query.limit.unwrap_or(100).clamp(1, MAX_AUDIT_LOG_LIMIT);
+
+    let entries = db.get_audit_log(&query, limit).await?;
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+// ====== H3 AUDIT HANDLERS ======
+
+/// H3 denetim işinin durum endpoint'i
+const H3_AUDIT_PATH: &str = "/api/v1/admin/h3-audit";
+
+// H3 index denetimini başlatma endpoint'i (POST)
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/h3-audit",
+    tag = "admin",
+    request_body = H3AuditRequest,
+    responses(
+        (status = 202, description = "Denetim arka planda başlatıldı; ilerleme GET ile izlenir", body = H3AuditStatus),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Çalışmakta olan bir denetim var", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Doğrulama hatası", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn start_h3_audit(
+    request: Valid<H3AuditRequest>,
+    principal: web::ReqData<Principal>,
+    jobs: web::Data<H3AuditJobs>,
+    db: web::Data<Database>,
+) -> ApiResult {
+    let batch_size = request.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    let status = H3AuditJobs::start(jobs, db, request.fix, batch_size)
+        .ok_or_else(|| AppError::conflict("h3_audit_running", "Çalışmakta olan bir H3 denetimi var"))?;
+
+    tracing::info!(job_id = %status.id, fix = status.fix, actor = %principal.actor_id(), "H3 denetimi başlatıldı");
+    Ok(HttpResponse::Accepted()
+        .insert_header((header::LOCATION, H3_AUDIT_PATH))
+        .json(status))
+}
+
+// Son H3 denetiminin durumu endpoint'i (GET)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/h3-audit",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Çalışan veya son tamamlanan denetimin durumu ve uyuşmazlıkları", body = H3AuditStatus),
+        (status = 401, description = "Kimlik doğrulaması gerekli", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Yetersiz kapsam", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Henüz denetim çalıştırılmadı", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("api_key" = ["admin"]))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_h3_audit(jobs: web::Data<H3AuditJobs>) -> ApiResult {
+    let status = jobs
+        .latest()
+        .ok_or_else(|| AppError::not_found("Henüz H3 denetimi çalıştırılmadı"))?;
+    Ok(HttpResponse::Ok().json(status))
 }
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::h3_utils::{coords_to_h3, h3_to_coords};
+use crate::models::{H3HeatmapCell, H3HeatmapResponse, HeatmapDelta, HeatmapLayer, HeatmapMetric, HeatmapPoint};
+
+/// Katman kayıtlarını verilen çözünürlükteki H3 hücrelerinde toplayıp hücre başına metriği hesaplar
+///
+/// `sum` ve `avg` metriklerinde alanı boş kayıtlar atlanır; böylece kapasitesi bilinmeyen limanlar ortalamayı
+/// düşürmez. Hücre merkezleri kayıt koordinatlarının ortalamasıdır (gizlilik modunda H3 hücre merkezine çekilir).
+pub fn aggregate(
+    layer: HeatmapLayer,
+    metric: HeatmapMetric,
+    field: Option<String>,
+    points: &[HeatmapPoint],
+    resolution: u8,
+) -> H3HeatmapResponse {
+    // Hücre başına (kayıt sayısı, değer toplamı, enlem toplamı, boylam toplamı)
+    let mut groups: HashMap<String, (i64, f64, f64, f64)> = HashMap::new();
+    for point in points {
+        let value = match metric {
+            HeatmapMetric::Count => 1.0,
+            HeatmapMetric::Sum | HeatmapMetric::Avg => match point.value {
+                Some(value) => value,
+                None => continue,
+            },
+        };
+        if let Ok(h3_index) = coords_to_h3(point.latitude, point.longitude, resolution) {
+            let group = groups.entry(h3_index).or_default();
+            group.0 += 1;
+            group.1 += value;
+            group.2 += point.latitude;
+            group.3 += point.longitude;
+        }
+    }
+
+    let (mut total_count, mut total_sum) = (0i64, 0.0);
+    let mut cells: Vec<H3HeatmapCell> = groups
+        .into_iter()
+        .map(|(h3_index, (count, sum, lat_sum, lng_sum))| {
+            total_count += count;
+            total_sum += sum;
+            H3HeatmapCell {
+                h3_index,
+                count,
+                value: metric_value(metric, count, sum),
+                user_count: (layer == HeatmapLayer::Users).then_some(count),
+                center_latitude: lat_sum / count as f64,
+                center_longitude: lng_sum / count as f64,
+            }
+        })
+        .collect();
+
+    // Değere göre sırala (eşitlikte index'e göre, sıralama kararlı olsun)
+    cells.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.h3_index.cmp(&b.h3_index)));
+
+    H3HeatmapResponse {
+        layer,
+        metric,
+        field,
+        cells,
+        total_count,
+        total_value: metric_value(metric, total_count, total_sum),
+        total_users: (layer == HeatmapLayer::Users).then_some(total_count),
+        resolution,
+        window: None,
+        privacy: None,
+    }
+}
+
+/// Aynı çözünürlükteki iki dönemin hücre sayılarını karşılaştırır; en çok artan hücreler önce gelir
+///
+/// Yalnızca bir dönemde kaydı olan hücreler diğer dönemde sıfır sayılır.
+pub fn compare(baseline: &[H3HeatmapCell], current: &[H3HeatmapCell]) -> Vec<HeatmapDelta> {
+    let mut counts: HashMap<&str, (i64, i64)> = HashMap::new();
+    for cell in baseline {
+        counts.entry(cell.h3_index.as_str()).or_default().0 += cell.count;
+    }
+    for cell in current {
+        counts.entry(cell.h3_index.as_str()).or_default().1 += cell.count;
+    }
+
+    let mut deltas: Vec<HeatmapDelta> = counts
+        .into_iter()
+        .filter_map(|(h3_index, (baseline_count, count))| {
+            let (center_latitude, center_longitude) = h3_to_coords(h3_index).ok()?;
+            Some(HeatmapDelta {
+                h3_index: h3_index.to_string(),
+                baseline_count,
+                count,
+                delta: count - baseline_count,
+                delta_percent: (baseline_count > 0)
+                    .then(|| (count - baseline_count) as f64 / baseline_count as f64 * 100.0),
+                center_latitude,
+                center_longitude,
+            })
+        })
+        .collect();
+    deltas.sort_by(|a, b| b.delta.cmp(&a.delta).then_with(|| a.h3_index.cmp(&b.h3_index)));
+    deltas
+}
+
+fn metric_value(metric: HeatmapMetric, count: i64, sum: f64) -> f64 {
+    match metric {
+        HeatmapMetric::Count | HeatmapMetric::Sum => sum,
+        HeatmapMetric::Avg if count > 0 => sum / count as f64,
+        HeatmapMetric::Avg => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_metrics_skip_unknown_values() {
+        let point = |latitude, longitude, value| HeatmapPoint { latitude, longitude, value };
+        // Aynı hücrede iki kapasiteli ve bir kapasitesiz liman, başka hücrede bir liman
+        let points = [
+            point(41.0082, 28.9784, Some(1000.0)),
+            point(41.0083, 28.9785, Some(3000.0)),
+            point(41.0084, 28.9786, None),
+            point(36.8, 34.6, Some(500.0)),
+        ];
+
+        let count = aggregate(HeatmapLayer::Ports, HeatmapMetric::Count, None, &points, 5);
+        assert_eq!(count.total_count, 4);
+        assert_eq!(count.cells[0].value, 3.0);
+        assert_eq!(count.cells[0].user_count, None);
+        assert_eq!(count.total_users, None);
+
+        let sum = aggregate(HeatmapLayer::Ports, HeatmapMetric::Sum, Some("capacity".to_string()), &points, 5);
+        assert_eq!(sum.total_count, 3);
+        assert_eq!(sum.cells[0].value, 4000.0);
+        assert_eq!(sum.total_value, 4500.0);
+
+        let avg = aggregate(HeatmapLayer::Ports, HeatmapMetric::Avg, Some("capacity".to_string()), &points, 5);
+        assert_eq!(avg.cells[0].value, 2000.0);
+        assert_eq!(avg.cells[1].value, 500.0);
+        assert_eq!(avg.total_value, 1500.0);
+
+        // Kullanıcı katmanında eski alanlar doldurulur
+        let users = aggregate(HeatmapLayer::Users, HeatmapMetric::Count, None, &points, 5);
+        assert_eq!(users.cells[0].user_count, Some(3));
+        assert_eq!(users.total_users, Some(4));
+    }
+
+    #[test]
+    fn test_compare_reports_growth_per_cell() {
+        let point = |latitude, longitude| HeatmapPoint { latitude, longitude, value: None };
+        let istanbul = point(41.0082, 28.9784);
+        let izmir = point(38.4237, 27.1428);
+        let ankara = point(39.9334, 32.8597);
+
+        let baseline = aggregate(HeatmapLayer::Users, HeatmapMetric::Count, None, &[istanbul, istanbul, izmir], 5);
+        let current = aggregate(HeatmapLayer::Users, HeatmapMetric::Count, None, &[istanbul, istanbul, istanbul, istanbul, ankara], 5);
+        let deltas = compare(&baseline.cells, &current.cells);
+
+        let summary: Vec<(i64, i64, i64, Option<f64>)> =
+            deltas.iter().map(|d| (d.baseline_count, d.count, d.delta, d.delta_percent)).collect();
+        assert_eq!(summary, [(2, 4, 2, Some(100.0)), (0, 1, 1, None), (1, 0, -1, Some(-100.0))][..]);
+        assert_eq!(deltas[0].h3_index, baseline.cells[0].h3_index);
+    }
+}
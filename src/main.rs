@@ -1,3 +1,4 @@
+mod auth;
 mod handlers;
 mod routes;
 mod config;
@@ -5,117 +6,219 @@ mod models;
 mod database;
 mod setup;
 mod h3_utils;
+mod oidc;
+mod validation;
+mod error;
+mod purge;
+mod bulk;
+mod privacy;
+mod crypto;
+mod metrics;
+mod telemetry;
+mod otlp;
+mod rate_limit;
+mod cli;
+mod doctor;
+mod seed;
+mod h3_audit;
+mod catchment;
+mod heatmap;
 
-use actix_web::{web, App, HttpServer, middleware::Logger};
+use actix_web::{web, App, HttpServer, middleware::from_fn};
 use actix_cors::Cors;
 use sqlx::postgres::PgPoolOptions;
 use routes::configure_routes;
+use clap::Parser;
+use cli::{Cli, Command};
 use config::AppConfig;
 use database::Database;
+use oidc::OidcValidator;
 // Swagger UI imports
-use utoipa::OpenApi;
+use utoipa::{Modify, OpenApi};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa_swagger_ui::SwaggerUi;
 
-// Import handler functions for Swagger documentation
-use crate::handlers::{
-    index, health_check, create_user, get_user, get_all_users, update_user, delete_user, get_stats,
-    get_all_ports, create_port, find_nearest_port, get_ports_by_country, get_ports_by_type
-};
+// Swagger dokümantasyonuna Bearer (API anahtarı) güvenlik şemasını ekler
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // .env dosyasını yükle
     dotenvy::dotenv().ok();
-    
-    let config = AppConfig::from_env();
-    
-    // Logger'ı ayarla
-    unsafe {
-        std::env::set_var("RUST_LOG", &config.log_level);
-    }
-    env_logger::init();
 
-    // Otomatik sistem kurulumu
-    println!("🚀 Otomatik sistem kurulumu başlatılıyor...");
-    
-    // PostgreSQL'i otomatik başlat
-    if let Err(e) = setup::ensure_postgresql_running().await {
-        eprintln!("⚠️  PostgreSQL otomatik başlatılamadı: {}", e);
-        eprintln!("📝 Manuel olarak başlatmayı deneyin...");
+    let cli = Cli::parse();
+    let config_file = cli.config_file();
+    match cli.command {
+        None | Some(Command::Serve) => serve(cli::load_config(config_file.as_deref())).await,
+        Some(command) => std::process::exit(cli::run(command, config_file.as_deref()).await),
     }
-    
-    // Veritabanını otomatik oluştur
-    if let Err(e) = setup::ensure_database_exists(&config.database_url).await {
-        eprintln!("⚠️  Veritabanı otomatik oluşturulamadı: {}", e);
-        eprintln!("📝 Manuel olarak oluşturmayı deneyin...");
+}
+
+// HTTP sunucusunu başlatır: migrasyonları uygular, email'leri etkin anahtara taşır ve istekleri kabul eder
+async fn serve(config: AppConfig) -> std::io::Result<()> {
+    // Yapılandırılmış loglama (RUST_LOG filtresi, LOG_FORMAT biçimi)
+    if let Err(e) = telemetry::init(&config) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
     }
 
     // Veritabanı pool'u oluştur
-    println!("🔌 PostgreSQL'e bağlanılıyor: {}", &config.database_url.replace(|c: char| c.is_ascii_digit(), "*"));
+    tracing::info!(
+        database_url = %config.redacted().database.url,
+        "PostgreSQL'e bağlanılıyor"
+    );
     
     let pool = match PgPoolOptions::new()
-        .max_connections(config.database_max_connections)
-        .connect(&config.database_url)
+        .max_connections(config.database.max_connections)
+        .connect(&config.database.url)
         .await
     {
         Ok(pool) => pool,
         Err(e) => {
-            eprintln!("❌ Veritabanına bağlanılamadı: {}", e);
-            eprintln!("💡 İpuçları:");
-            eprintln!("   1. PostgreSQL server'ınız çalıştığından emin olun:");
-            eprintln!("      brew services start postgresql    # macOS ile");
-            eprintln!("      sudo service postgresql start     # Linux ile");
-            eprintln!("");
-            eprintln!("   2. Veritabanını oluşturun:");
-            eprintln!("      createdb rustmicro               # veya");
-            eprintln!("      psql -U postgres -c \"CREATE DATABASE rustmicro;\"");
-            eprintln!("");
-            eprintln!("   3. DATABASE_URL'nizi kontrol edin (.env dosyası):");
-            eprintln!("      DATABASE_URL={}", config.database_url);
-            eprintln!("");
+            tracing::error!(
+                error = %e,
+                hint = "Sorunun nedeni için: rustmicro doctor (veritabanı yoksa: rustmicro db create)",
+                "Veritabanına bağlanılamadı"
+            );
             std::process::exit(1);
         }
     };
 
-    println!("✅ Veritabanı bağlantısı başarılı!");
+    tracing::info!("Veritabanı bağlantısı başarılı");
 
     // Veritabanı migrasyonlarını çalıştır
-    println!("🔄 Veritabanı migrasyonları kontrol ediliyor...");
-    match sqlx::migrate!("./migrations").run(&pool).await {
-        Ok(_) => println!("✅ Veritabanı migrasyonları başarıyla tamamlandı!"),
+    tracing::info!("Veritabanı migrasyonları kontrol ediliyor");
+    match database::MIGRATOR.run(&pool).await {
+        Ok(_) => tracing::info!("Veritabanı migrasyonları başarıyla tamamlandı"),
         Err(e) => {
-            eprintln!("❌ Migrasyon hatası: {}", e);
-            eprintln!("💡 İpucuları:");
-            eprintln!("   - PostgreSQL server'ınız çalıştığından emin olun");
-            eprintln!("   - DATABASE_URL'nin doğru olduğunu kontrol edin");
-            eprintln!("   - Veritabanının mevcut olduğunu kontrol edin");
+            tracing::error!(error = %e, "Migrasyon hatası");
             std::process::exit(1);
         }
     };
 
-    let db = web::Data::new(Database::new(pool));
+    // Email şifreleme anahtarları
+    let cipher = match crypto::EmailCipher::from_config(&config) {
+        Ok(cipher) => cipher,
+        Err(e) => {
+            tracing::error!(
+                error = %e,
+                hint = "Anahtar üretmek için: openssl rand -base64 32 (EMAIL_ENCRYPTION_KEYS=v1:<anahtar>, EMAIL_BLIND_INDEX_KEY=<anahtar>)",
+                "Email şifreleme konfigürasyon hatası"
+            );
+            std::process::exit(1);
+        }
+    };
+    let db = web::Data::new(Database::new(pool, cipher));
 
-    // Başarı banner'ını göster
-    setup::print_success_banner();
+    // Düz metin ve eski anahtarla şifrelenmiş email'leri etkin anahtara taşı (istekler kabul edilmeden önce)
+    match db.reencrypt_emails().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(users = count, "Kullanıcı email'leri etkin anahtarla şifrelendi"),
+        Err(e) => {
+            tracing::error!(error = %e, "Email'ler şifrelenemedi");
+            std::process::exit(1);
+        }
+    }
+    match db.hash_audit_log_emails().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(entries = count, "Denetim kayıtlarındaki email'ler kör indeksle değiştirildi"),
+        Err(e) => {
+            tracing::error!(error = %e, "Denetim kayıtlarındaki email'ler güncellenemedi");
+            std::process::exit(1);
+        }
+    }
+    match db.coarsen_audit_log_locations().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(entries = count, "Denetim kayıtlarındaki konumlar kullanıcıların hassasiyetine indirgendi"),
+        Err(e) => {
+            tracing::error!(error = %e, "Denetim kayıtlarındaki konumlar güncellenemedi");
+            std::process::exit(1);
+        }
+    }
+    let app_config = web::Data::new(config.clone());
+    let privacy_budget = web::Data::new(privacy::PrivacyBudget::new(config.privacy.heatmap_privacy_budget));
+    let metrics = web::Data::new(metrics::Metrics::new().expect("Prometheus metrikleri kaydedilemedi"));
+    let rate_limiter = web::Data::new(rate_limit::RateLimiter::new(&config));
+    let h3_audit_jobs = web::Data::new(h3_audit::H3AuditJobs::new());
+    let catchment_cache = web::Data::new(catchment::CatchmentCache::new(
+        std::time::Duration::from_secs(config.h3.catchment_cache_ttl_secs),
+        config.privacy.heatmap_min_count,
+    ));
+
+    // Saklama süresi dolmuş silinmiş kayıtların temizlenmesi
+    purge::spawn_purge_job(
+        db.clone(),
+        config.privacy.soft_delete_retention_days,
+        std::time::Duration::from_secs(config.privacy.purge_interval_secs),
+    );
+
+    // OIDC bearer token doğrulaması (yapılandırılmışsa)
+    let oidc = match OidcValidator::from_config(&config) {
+        Ok(Some(validator)) => {
+            match validator.jwks().refresh().await {
+                Ok(count) => tracing::info!(keys = count, "OIDC etkin: JWKS yüklendi"),
+                Err(e) => tracing::warn!(error = %e, "JWKS yüklenemedi, ilk istekte tekrar denenecek"),
+            }
+            Some(web::Data::new(validator))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            tracing::error!(error = %e, "OIDC konfigürasyon hatası");
+            std::process::exit(1);
+        }
+    };
+
+    // Başlangıç özetini logla
+    setup::log_startup_summary(&config);
 
     // Define OpenAPI documentation
     #[derive(OpenApi)]
     #[openapi(
         paths(
             handlers::index,
-            handlers::health_check,
+            handlers::liveness,
+            handlers::readiness,
+            handlers::metrics,
             handlers::get_stats,
             handlers::get_all_users,
             handlers::create_user,
             handlers::get_user,
             handlers::update_user,
             handlers::delete_user,
+            handlers::restore_user,
+            handlers::import_users,
+            handlers::export_users,
             handlers::get_all_ports,
+            handlers::get_port,
             handlers::create_port,
+            handlers::delete_port,
+            handlers::restore_port,
             handlers::find_nearest_port,
             handlers::get_ports_by_country,
             handlers::get_ports_by_type,
+            handlers::get_port_catchments,
+            handlers::get_coverage_gaps,
             handlers::get_user_heatmap,
+            handlers::get_heatmap_comparison,
+            handlers::get_all_api_keys,
+            handlers::create_api_key,
+            handlers::revoke_api_key,
+            handlers::get_audit_log,
+            handlers::get_user_subject_access,
+            handlers::erase_user,
+            handlers::start_h3_audit,
+            handlers::get_h3_audit,
         ),
         components(
             schemas(
@@ -130,9 +233,58 @@ async fn main() -> std::io::Result<()> {
                 models::FindNearestPortRequest,
                 models::H3HeatmapResponse,
                 models::H3HeatmapCell,
+                models::HeatmapLayer,
+                models::HeatmapMetric,
+                models::TimeRange,
+                models::HeatmapDelta,
+                models::HeatmapComparison,
+                models::HealthStatus,
+                models::DatabaseCheck,
+                models::MigrationCheck,
+                models::PoolCheck,
+                models::ReadinessChecks,
+                models::ReadinessResponse,
+                models::HeatmapPrivacy,
+                privacy::SuppressionMode,
                 models::DateTimeSchema,
+                models::CreateApiKey,
+                models::ApiKeyResponse,
+                models::CreatedApiKeyResponse,
+                auth::Scope,
+                validation::FieldError,
+                error::ProblemDetails,
+                models::BulkFormat,
+                models::ImportReport,
+                models::ImportAborted,
+                models::ImportRowError,
+                models::LocationErasure,
+                models::LocationPrecision,
+                models::EraseUserRequest,
+                models::SubjectAccessExport,
+                models::AuditLogEntry,
+                models::AuditEntity,
+                models::AuditAction,
+                models::H3AuditRequest,
+                models::H3AuditStatus,
+                models::H3AuditCounts,
+                models::H3Mismatch,
+                models::JobState,
+                models::GeoJsonMultiPolygon,
+                models::PortCatchment,
+                models::CatchmentReport,
+                models::CatchmentJob,
+                models::CoverageFormat,
+                models::CoverageGap,
+                models::CoverageGapReport,
+                models::CoverageGapFeature,
+                models::CoverageGapCollection,
                 handlers::ApiResponse,
                 handlers::StatsResponse,
+                models::SignupInterval,
+                models::SignupCount,
+                models::UserStats,
+                models::PortCount,
+                models::PortStats,
             )
         ),
         tags(
@@ -140,20 +292,54 @@ async fn main() -> std::io::Result<()> {
             (name = "users", description = "User management endpoints"),
             (name = "ports", description = "Port management endpoints"),
             (name = "stats", description = "Statistics endpoints"),
-        )
+            (name = "admin", description = "API key administration endpoints"),
+        ),
+        modifiers(&SecurityAddon)
     )]
     struct ApiDoc;
 
+    let cors_config = config.cors.clone();
     HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
+        let mut cors = Cors::default()
             .allow_any_method()
-            .allow_any_header();
+            .allow_any_header()
+            .max_age(cors_config.max_age_secs)
+            .expose_headers([
+                telemetry::REQUEST_ID_HEADER,
+                rate_limit::RATE_LIMIT_LIMIT,
+                rate_limit::RATE_LIMIT_REMAINING,
+                rate_limit::RATE_LIMIT_RESET,
+                rate_limit::RATE_LIMIT_POLICY,
+                "retry-after",
+            ]);
+        if cors_config.allowed_origins.iter().any(|origin| origin == "*") {
+            cors = cors.allow_any_origin();
+        } else {
+            for origin in &cors_config.allowed_origins {
+                cors = cors.allowed_origin(origin);
+            }
+        }
 
-        App::new()
+        let mut app = App::new()
             .app_data(db.clone())
+            .app_data(app_config.clone())
+            .app_data(privacy_budget.clone())
+            .app_data(metrics.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(h3_audit_jobs.clone())
+            .app_data(catchment_cache.clone())
+            .app_data(web::JsonConfig::default().error_handler(error::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(error::query_error_handler));
+        if let Some(oidc) = &oidc {
+            app = app.app_data(oidc.clone());
+        }
+
+        app
+            .wrap(from_fn(rate_limit::enforce))
+            .wrap(from_fn(auth::authenticate))
             .wrap(cors)
-            .wrap(Logger::default())
+            .wrap(from_fn(metrics::track_requests))
+            .wrap(from_fn(telemetry::request_id))
             .configure(configure_routes)
             // Swagger UI integration
             .service(
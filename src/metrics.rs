@@ -0,0 +1,159 @@
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, Error};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::database::Database;
+
+/// Metrik adlarının ön eki
+const NAMESPACE: &str = "rustmicro";
+
+/// Eşleşen bir route'u olmayan istekler için etiket (bilinmeyen path'ler kardinaliteyi artırmasın diye)
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Uygulama metrikleri; `/metrics` endpoint'inde Prometheus metin formatında yayınlanır
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    operation_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+    users: IntGauge,
+    ports: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "İşlenen HTTP istek sayısı").namespace(NAMESPACE),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP istek süresi").namespace(NAMESPACE),
+            &["method", "route", "status"],
+        )?;
+        let operation_duration = HistogramVec::new(
+            HistogramOpts::new("operation_duration_seconds", "Maliyetli işlemlerin süresi").namespace(NAMESPACE),
+            &["operation"],
+        )?;
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Bağlantı havuzundaki bağlantılar").namespace(NAMESPACE),
+            &["state"],
+        )?;
+        let db_pool_max_connections = IntGauge::with_opts(
+            Opts::new("db_pool_max_connections", "Bağlantı havuzunun en fazla bağlantı sayısı").namespace(NAMESPACE),
+        )?;
+        let users = IntGauge::with_opts(Opts::new("users", "Silinmemiş kullanıcı sayısı").namespace(NAMESPACE))?;
+        let ports = IntGauge::with_opts(Opts::new("ports", "Silinmemiş liman sayısı").namespace(NAMESPACE))?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(operation_duration.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        registry.register(Box::new(db_pool_max_connections.clone()))?;
+        registry.register(Box::new(users.clone()))?;
+        registry.register(Box::new(ports.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            operation_duration,
+            db_pool_connections,
+            db_pool_max_connections,
+            users,
+            ports,
+        })
+    }
+
+    /// Tamamlanan bir HTTP isteğini kaydeder
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration.with_label_values(&labels).observe(seconds);
+    }
+
+    /// Bir işlemin süresini ölçmeye başlar; dönen zamanlayıcı düşürüldüğünde süre kaydedilir
+    pub fn time_operation(&self, operation: &str) -> prometheus::HistogramTimer {
+        self.operation_duration.with_label_values(&[operation]).start_timer()
+    }
+
+    /// Havuz ve alan metriklerini günceller, ardından tüm metrikleri metin formatında döndürür
+    pub async fn render(&self, db: &Database) -> Result<String, prometheus::Error> {
+        let (size, idle, max) = db.pool_usage();
+        self.db_pool_connections.with_label_values(&["idle"]).set(idle as i64);
+        self.db_pool_connections.with_label_values(&["in_use"]).set(size.saturating_sub(idle) as i64);
+        self.db_pool_max_connections.set(max as i64);
+
+        // Sayım alınamazsa son bilinen değer yayınlanmaya devam eder
+        match db.count_users().await {
+            Ok(count) => self.users.set(count),
+            Err(e) => tracing::warn!(error = %e, "Metrikler: kullanıcı sayısı alınamadı"),
+        }
+        match db.count_ports().await {
+            Ok(count) => self.ports.set(count),
+            Err(e) => tracing::warn!(error = %e, "Metrikler: liman sayısı alınamadı"),
+        }
+
+        self.encode()
+    }
+
+    fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+/// Her isteğin süresini ve durum kodunu route şablonuna göre (örn: `/api/v1/users/{id}`) kaydeden middleware
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let started = Instant::now();
+    let metrics = req.app_data::<web::Data<Metrics>>().cloned();
+    let method = req.method().to_string();
+
+    let result = next.call(req).await;
+
+    if let Some(metrics) = metrics {
+        let (route, status) = match &result {
+            Ok(res) => (res.request().match_pattern(), res.status().as_u16()),
+            Err(e) => (None, e.as_response_error().status_code().as_u16()),
+        };
+        metrics.observe_request(
+            &method,
+            route.as_deref().unwrap_or(UNMATCHED_ROUTE),
+            status,
+            started.elapsed().as_secs_f64(),
+        );
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_metrics_are_labelled_by_route() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_request("GET", "/api/v1/users/{id}", 200, 0.01);
+        metrics.observe_request("GET", "/api/v1/users/{id}", 200, 0.02);
+        drop(metrics.time_operation("find_nearest_port"));
+
+        let text = metrics.encode().unwrap();
+        assert!(text.contains(r#"rustmicro_http_requests_total{method="GET",route="/api/v1/users/{id}",status="200"} 2"#));
+        assert!(text.contains(r#"rustmicro_operation_duration_seconds_count{operation="find_nearest_port"} 1"#));
+    }
+}
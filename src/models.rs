@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+use crate::auth::Scope;
+use crate::h3_utils::MultiPolygonCoordinates;
+use crate::privacy::SuppressionMode;
+use crate::validation::FieldError;
 
 
 // Custom DateTime wrapper for Swagger
@@ -22,44 +26,127 @@ pub struct User {
     pub id: Uuid,
     /// Kullanıcının adı
     pub name: String,
-    /// Kullanıcının e-posta adresi
+    /// Kullanıcının e-posta adresi (veritabanında şifreli saklanır, okunduktan sonra çözülür;
+    /// denetim kayıtlarına yazılmaz)
+    #[sqlx(default)]
+    #[serde(skip_serializing)]
     pub email: String,
+    /// Şifreli email (`nonce || ciphertext`)
+    #[serde(skip)]
+    pub email_ciphertext: Vec<u8>,
+    /// Email'i şifreleyen anahtarın kimliği
+    #[serde(skip)]
+    pub email_key_id: String,
+    /// Email'in kör indeksi (HMAC-SHA256); denetim kayıtlarında email yerine bu tutulur
+    pub email_hash: String,
     /// Kullanıcının enlemi (isteğe bağlı)
     pub latitude: Option<f64>,
     /// Kullanıcının boylamı (isteğe bağlı)
     pub longitude: Option<f64>,
     /// Kullanıcının H3 indeksi (isteğe bağlı)
     pub h3_index: Option<String>,
+    /// Kullanıcının onay verdiği konum hassasiyeti
+    pub location_precision: LocationPrecision,
     /// Kullanıcının oluşturulma tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub created_at: DateTime<Utc>,
     /// Kullanıcının güncellenme tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub updated_at: DateTime<Utc>,
+    /// Kullanıcının silinme tarihi (silinmemişse boş)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Kişisel verilerin silinme (GDPR) tarihi
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub erased_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// Kullanıcının konumunun hangi hassasiyetle saklanacağı (sıralama en hassastan en kabaya doğrudur)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum LocationPrecision {
+    /// Tam koordinat
+    #[default]
+    Exact,
+    /// Mahalle düzeyi (H3 çözünürlük 8 hücre merkezi, ~0.7 km²)
+    Neighbourhood,
+    /// Şehir düzeyi (H3 çözünürlük 5 hücre merkezi, ~250 km²)
+    City,
+    /// Konum saklanmaz
+    None,
+}
+
+impl LocationPrecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationPrecision::Exact => "exact",
+            LocationPrecision::Neighbourhood => "neighbourhood",
+            LocationPrecision::City => "city",
+            LocationPrecision::None => "none",
+        }
+    }
+
+    /// Koordinatların indirgeneceği H3 çözünürlüğü (`exact` ve `none` için yok)
+    pub fn resolution(&self) -> Option<u8> {
+        match self {
+            LocationPrecision::Neighbourhood => Some(8),
+            LocationPrecision::City => Some(5),
+            LocationPrecision::Exact | LocationPrecision::None => None,
+        }
+    }
+}
+
+impl std::str::FromStr for LocationPrecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(LocationPrecision::Exact),
+            "neighbourhood" => Ok(LocationPrecision::Neighbourhood),
+            "city" => Ok(LocationPrecision::City),
+            "none" => Ok(LocationPrecision::None),
+            _ => Err(format!("Bilinmeyen konum hassasiyeti: {}", s)),
+        }
+    }
+}
+
+/// Enlem ve boylam birlikte verilmeli ya da hiç verilmemeli
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateUser {
     /// Kullanıcının adı
+    #[schema(min_length = 1, max_length = 255, example = "Ahmet Yılmaz")]
     pub name: String,
     /// Kullanıcının e-posta adresi
+    #[schema(max_length = 254, pattern = r"^[^@\s]+@[^@\s]+\.[^@\s]+$", example = "ahmet@example.com")]
     pub email: String,
     /// Kullanıcının enlemi (isteğe bağlı)
+    #[schema(minimum = -90, maximum = 90, example = 41.0082)]
     pub latitude: Option<f64>,
     /// Kullanıcının boylamı (isteğe bağlı)
+    #[schema(minimum = -180, maximum = 180, example = 28.9784)]
     pub longitude: Option<f64>,
+    /// Konum hassasiyeti (varsayılan: exact); koordinatlar bu hassasiyete indirgenerek saklanır
+    pub location_precision: Option<LocationPrecision>,
 }
 
+/// Enlem ve boylam birlikte verilmeli ya da hiç verilmemeli
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateUser {
     /// Kullanıcının adı (isteğe bağlı)
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
     /// Kullanıcının e-posta adresi (isteğe bağlı)
+    #[schema(max_length = 254, pattern = r"^[^@\s]+@[^@\s]+\.[^@\s]+$")]
     pub email: Option<String>,
     /// Kullanıcının enlemi (isteğe bağlı)
+    #[schema(minimum = -90, maximum = 90)]
     pub latitude: Option<f64>,
     /// Kullanıcının boylamı (isteğe bağlı)
+    #[schema(minimum = -180, maximum = 180)]
     pub longitude: Option<f64>,
+    /// Konum hassasiyeti (isteğe bağlı); değişirse saklanan konum yeni hassasiyete indirgenir
+    pub location_precision: Option<LocationPrecision>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -76,12 +163,20 @@ pub struct UserResponse {
     pub longitude: Option<f64>,
     /// Kullanıcının H3 indeksi (isteğe bağlı)
     pub h3_index: Option<String>,
+    /// Kullanıcının onay verdiği konum hassasiyeti
+    pub location_precision: LocationPrecision,
     /// Kullanıcının oluşturulma tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub created_at: DateTime<Utc>,
     /// Kullanıcının güncellenme tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub updated_at: DateTime<Utc>,
+    /// Kullanıcının silinme tarihi (silinmemişse boş)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Kişisel verilerin silinme (GDPR) tarihi
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub erased_at: Option<DateTime<Utc>>,
 }
 
 impl From<User> for UserResponse {
@@ -93,13 +188,147 @@ impl From<User> for UserResponse {
             latitude: user.latitude,
             longitude: user.longitude,
             h3_index: user.h3_index,
+            location_precision: user.location_precision,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+            erased_at: user.erased_at,
         }
     }
 }
 
+/// Liste ve tekil sorgularda silinmiş kayıtların dahil edilip edilmeyeceği
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct IncludeDeletedQuery {
+    /// Silinmiş (geri yüklenebilir) kayıtları da döndür (varsayılan: false)
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+// Toplu içe/dışa aktarma modelleri
+
+/// Toplu içe/dışa aktarma formatı
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkFormat {
+    /// Başlık satırlı CSV (name,email,latitude,longitude)
+    Csv,
+    /// Satır başına bir JSON nesnesi
+    Ndjson,
+}
+
+impl BulkFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BulkFormat::Csv => "text/csv; charset=utf-8",
+            BulkFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BulkFormat::Csv => "csv",
+            BulkFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportUsersQuery {
+    /// Gövde formatı; verilmezse Content-Type'tan çıkarılır (text/csv, application/x-ndjson)
+    #[param(inline)]
+    pub format: Option<BulkFormat>,
+    /// true ise satırlar yalnızca doğrulanır, hiçbir kayıt eklenmez
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportUsersQuery {
+    /// Çıktı formatı (varsayılan: csv)
+    #[param(inline)]
+    pub format: Option<BulkFormat>,
+    /// Silinmiş kullanıcıları da dışa aktar (varsayılan: false)
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+/// İçe aktarmada hatalı bir satır
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowError {
+    /// Satır numarası (1'den başlar; CSV'de başlık satırı 1'dir)
+    pub row: u64,
+    /// Satırdaki hatalar
+    pub errors: Vec<FieldError>,
+}
+
+/// İçe aktarma raporu
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    /// Deneme modu; true ise hiçbir kayıt eklenmedi
+    pub dry_run: bool,
+    /// İşlenen veri satırı sayısı (boş satırlar hariç)
+    pub total_rows: u64,
+    /// Eklenen (deneme modunda eklenebilecek) kullanıcı sayısı
+    pub imported: u64,
+    /// Hatalı satır sayısı
+    pub failed: u64,
+    /// Satır bazında hatalar (en fazla 1000 satır raporlanır)
+    pub errors: Vec<ImportRowError>,
+    /// Hata listesi kırpıldıysa true
+    pub errors_truncated: bool,
+    /// İçe aktarma yarıda kesildiyse nedeni; o ana kadar eklenen gruplar geri alınmaz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aborted: Option<ImportAborted>,
+}
+
+/// Yarıda kesilen içe aktarmanın durumu
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportAborted {
+    /// Kesintiye yol açan hatanın sabit kodu (örn: "database_error")
+    pub code: String,
+    /// Bu satıra kadarki (dahil) hatasız satırların tümü eklendi; sonrakilerin hiçbiri eklenmedi
+    pub committed_through_row: u64,
+}
+
+// GDPR modelleri
+
+/// Silme talebinde konumun ne yapılacağı
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationErasure {
+    /// Konumu tamamen sil
+    Delete,
+    /// Konumu düşük çözünürlüklü H3 üst hücresinin merkezine indirge (heatmap toplamları korunur)
+    Coarsen,
+}
+
+/// Kullanıcının kişisel verilerini silme talebi
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EraseUserRequest {
+    /// Konumun ne yapılacağı
+    pub location: LocationErasure,
+    /// `coarsen` için H3 çözünürlüğü (varsayılan: 5, en fazla: 6)
+    #[schema(minimum = 0, maximum = 6, example = 5)]
+    pub resolution: Option<u8>,
+}
+
+/// Bir kullanıcı hakkında tutulan tüm veriler (GDPR erişim talebi)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubjectAccessExport {
+    /// Belgenin oluşturulma zamanı
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub generated_at: DateTime<Utc>,
+    /// Kullanıcı kaydı (silinmiş olsa bile)
+    pub user: UserResponse,
+    /// Kullanıcıyla ilgili tüm denetim kayıtları (en yeni önce)
+    pub audit_log: Vec<AuditLogEntry>,
+}
+
 // Port (Liman) modelleri
+
+/// Geçerli liman tipleri (ports tablosundaki CHECK kısıtı ile aynı)
+pub const PORT_TYPES: [&str; 5] = ["container", "cruise", "cargo", "fishing", "ferry"];
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
 pub struct Port {
     /// Limanın benzersiz kimliği (UUID)
@@ -118,7 +347,7 @@ pub struct Port {
     pub longitude: f64,
     /// Limanın H3 indeksi
     pub h3_index: String,
-    /// Limanın tipi ("container", "cruise", "cargo", "fishing")
+    /// Limanın tipi ("container", "cruise", "cargo", "fishing", "ferry")
     pub port_type: String,
     /// TEU kapasitesi (isteğe bağlı)
     pub capacity: Option<i32>,
@@ -128,45 +357,64 @@ pub struct Port {
     /// Limanın güncellenme tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub updated_at: DateTime<Utc>,
+    /// Limanın silinme tarihi (silinmemişse boş)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreatePort {
     /// Limanın adı
+    #[schema(min_length = 1, max_length = 255, example = "Ambarlı Limanı")]
     pub name: String,
     /// Limanın IATA/ICAO kodu (örn: TRIST, TRMER)
+    #[schema(pattern = "^[A-Z0-9]{2,10}$", example = "TRAMB")]
     pub code: String,
     /// Limanın bulunduğu ülke
+    #[schema(min_length = 1, max_length = 255, example = "Turkey")]
     pub country: String,
     /// Limanın bulunduğu şehir
+    #[schema(min_length = 1, max_length = 255, example = "Istanbul")]
     pub city: String,
     /// Limanın enlemi
+    #[schema(minimum = -90, maximum = 90, example = 40.977)]
     pub latitude: f64,
     /// Limanın boylamı
+    #[schema(minimum = -180, maximum = 180, example = 28.685)]
     pub longitude: f64,
-    /// Limanın tipi ("container", "cruise", "cargo", "fishing")
+    /// Limanın tipi ("container", "cruise", "cargo", "fishing", "ferry")
+    #[schema(pattern = "^(container|cruise|cargo|fishing|ferry)$", example = "container")]
     pub port_type: String,
     /// TEU kapasitesi (isteğe bağlı)
+    #[schema(minimum = 0, example = 2500000)]
     pub capacity: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdatePort {
     /// Limanın adı (isteğe bağlı)
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
     /// Limanın IATA/ICAO kodu (örn: TRIST, TRMER) (isteğe bağlı)
+    #[schema(pattern = "^[A-Z0-9]{2,10}$")]
     pub code: Option<String>,
     /// Limanın bulunduğu ülke (isteğe bağlı)
+    #[schema(min_length = 1, max_length = 255)]
     pub country: Option<String>,
     /// Limanın bulunduğu şehir (isteğe bağlı)
+    #[schema(min_length = 1, max_length = 255)]
     pub city: Option<String>,
     /// Limanın enlemi (isteğe bağlı)
+    #[schema(minimum = -90, maximum = 90)]
     pub latitude: Option<f64>,
     /// Limanın boylamı (isteğe bağlı)
+    #[schema(minimum = -180, maximum = 180)]
     pub longitude: Option<f64>,
-    /// Limanın tipi ("container", "cruise", "cargo", "fishing") (isteğe bağlı)
+    /// Limanın tipi ("container", "cruise", "cargo", "fishing", "ferry") (isteğe bağlı)
+    #[schema(pattern = "^(container|cruise|cargo|fishing|ferry)$")]
     pub port_type: Option<String>,
     /// TEU kapasitesi (isteğe bağlı)
+    #[schema(minimum = 0)]
     pub capacity: Option<i32>,
 }
 
@@ -188,7 +436,7 @@ pub struct PortResponse {
     pub longitude: f64,
     /// Limanın H3 indeksi
     pub h3_index: String,
-    /// Limanın tipi ("container", "cruise", "cargo", "fishing")
+    /// Limanın tipi ("container", "cruise", "cargo", "fishing", "ferry")
     pub port_type: String,
     /// TEU kapasitesi (isteğe bağlı)
     pub capacity: Option<i32>,
@@ -198,13 +446,18 @@ pub struct PortResponse {
     /// Limanın güncellenme tarihi
     #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
     pub updated_at: DateTime<Utc>,
+    /// Limanın silinme tarihi (silinmemişse boş)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct FindNearestPortRequest {
     /// Arama yapılacak konumun enlemi
+    #[schema(minimum = -90, maximum = 90, example = 41.0082)]
     pub latitude: f64,
     /// Arama yapılacak konumun boylamı
+    #[schema(minimum = -180, maximum = 180, example = 28.9784)]
     pub longitude: f64,
 }
 
@@ -223,17 +476,84 @@ impl From<Port> for PortResponse {
             capacity: port.capacity,
             created_at: port.created_at,
             updated_at: port.updated_at,
+            deleted_at: port.deleted_at,
         }
     }
 }
 
 // H3 heatmap için model
+
+/// Heatmap'te toplanan kayıt katmanı
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapLayer {
+    /// Konumu olan kullanıcılar (gizlilik korumalı)
+    #[default]
+    Users,
+    /// Silinmemiş limanlar
+    Ports,
+}
+
+impl HeatmapLayer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeatmapLayer::Users => "users",
+            HeatmapLayer::Ports => "ports",
+        }
+    }
+
+    /// `sum` ve `avg` metriklerinde kullanılabilecek sayısal alanlar; ilki varsayılandır
+    pub fn value_fields(&self) -> &'static [&'static str] {
+        match self {
+            HeatmapLayer::Users => &[],
+            HeatmapLayer::Ports => &["capacity"],
+        }
+    }
+}
+
+/// Hücre başına hesaplanan metrik
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapMetric {
+    /// Hücredeki kayıt sayısı
+    #[default]
+    Count,
+    /// Alanın hücredeki toplamı
+    Sum,
+    /// Alanın hücredeki ortalaması
+    Avg,
+}
+
+/// Kayıtların oluşturulma zamanına göre süzüldüğü aralık: `[from, to)`; boş uçlar sınırsızdır
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+pub struct TimeRange {
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-02-01T00:00:00Z")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Heatmap'e girecek bir kayıt: konum ve (metrik bir alan istiyorsa) alanın değeri
+#[derive(Debug, Clone, Copy)]
+pub struct HeatmapPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub value: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct H3HeatmapCell {
     /// H3 hücre indeksi
     pub h3_index: String,
-    /// Hücredeki kullanıcı sayısı
-    pub user_count: i64,
+    /// Hücredeki kayıt sayısı (`sum`/`avg` metriklerinde yalnızca alanı dolu kayıtlar)
+    pub count: i64,
+    /// Metriğin hücredeki değeri (`count` metriğinde kayıt sayısı)
+    pub value: f64,
+    /// Hücredeki kullanıcı sayısı (yalnızca `users` katmanında; `count` ile aynı)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_count: Option<i64>,
     /// Hücrenin merkez enlemi
     pub center_latitude: f64,
     /// Hücrenin merkez boylamı
@@ -242,10 +562,703 @@ pub struct H3HeatmapCell {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct H3HeatmapResponse {
-    /// Heatmap hücreleri
+    pub layer: HeatmapLayer,
+    pub metric: HeatmapMetric,
+    /// `sum`/`avg` metriğinin hesaplandığı alan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Heatmap hücreleri (değeri en yüksek önce)
     pub cells: Vec<H3HeatmapCell>,
-    /// Toplam kullanıcı sayısı
-    pub total_users: i64,
+    /// Hücrelerdeki toplam kayıt sayısı (gizlilik modunda yalnızca yayınlanan hücrelerin toplamı)
+    pub total_count: i64,
+    /// Metriğin tüm kayıtlar üzerindeki değeri (`avg` metriğinde genel ortalama)
+    pub total_value: f64,
+    /// Toplam kullanıcı sayısı (yalnızca `users` katmanında; `total_count` ile aynı)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_users: Option<i64>,
     /// Heatmap için kullanılan H3 çözünürlüğü
     pub resolution: u8,
+    /// Kayıtların süzüldüğü oluşturulma zamanı aralığı (süzülmediyse boş)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<TimeRange>,
+    /// Gizlilik modunda uygulanan korumalar (tam sayımlarda boş)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<HeatmapPrivacy>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HeatmapCompareQuery {
+    /// Karşılaştırılacak katman (varsayılan: users)
+    #[param(inline)]
+    pub layer: Option<HeatmapLayer>,
+    /// H3 çözünürlüğü (0-15, varsayılan: 8)
+    pub resolution: Option<u8>,
+    /// Önceki dönemin başlangıcı (dahil)
+    #[param(value_type = String, format = DateTime)]
+    pub baseline_from: DateTime<Utc>,
+    /// Önceki dönemin sonu (hariç)
+    #[param(value_type = String, format = DateTime)]
+    pub baseline_to: DateTime<Utc>,
+    /// Sonraki dönemin başlangıcı (dahil)
+    #[param(value_type = String, format = DateTime)]
+    pub from: DateTime<Utc>,
+    /// Sonraki dönemin sonu (hariç)
+    #[param(value_type = String, format = DateTime)]
+    pub to: DateTime<Utc>,
+}
+
+/// Bir hücrenin iki dönemdeki kayıt sayıları
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeatmapDelta {
+    pub h3_index: String,
+    /// Önceki dönemde oluşturulan kayıtlar
+    pub baseline_count: i64,
+    /// Sonraki dönemde oluşturulan kayıtlar
+    pub count: i64,
+    /// `count - baseline_count`
+    pub delta: i64,
+    /// Önceki döneme göre yüzde değişim (önceki dönemde kayıt yoksa boş)
+    pub delta_percent: Option<f64>,
+    /// H3 hücre merkezinin enlemi
+    pub center_latitude: f64,
+    /// H3 hücre merkezinin boylamı
+    pub center_longitude: f64,
+}
+
+/// İki dönemin hücre bazında karşılaştırması
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeatmapComparison {
+    pub layer: HeatmapLayer,
+    pub resolution: u8,
+    pub baseline_window: TimeRange,
+    pub window: TimeRange,
+    /// Önceki dönemdeki toplam kayıt sayısı
+    pub baseline_total: i64,
+    /// Sonraki dönemdeki toplam kayıt sayısı
+    pub total: i64,
+    /// En çok artan hücreler önce
+    pub cells: Vec<HeatmapDelta>,
+}
+
+/// Gizlilik modunda uygulanan korumalar
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeatmapPrivacy {
+    /// Bir hücrenin yayınlanması için gereken en az kullanıcı sayısı
+    pub min_count: i64,
+    /// Eşiğin altındaki hücrelere uygulanan işlem
+    pub suppression: SuppressionMode,
+    /// Gizlenen (hiçbir hücrede yayınlanmayan) kullanıcı sayısı; gürültü varsa gürültülü sayımlardan hesaplanır
+    pub suppressed_users: i64,
+    /// Sayımlara eklenen Laplace gürültüsünün epsilon değeri (gürültü yoksa boş)
+    pub epsilon: Option<f64>,
+    /// Çağıranın bu pencerede kalan gizlilik bütçesi (gürültü yoksa boş)
+    pub budget_remaining: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HeatmapQuery {
+    /// Toplanacak katman (varsayılan: users)
+    #[param(inline)]
+    pub layer: Option<HeatmapLayer>,
+    /// Hücre başına metrik (varsayılan: count)
+    #[param(inline)]
+    pub metric: Option<HeatmapMetric>,
+    /// `sum`/`avg` için sayısal alan (ports: capacity; varsayılan: katmanın ilk alanı)
+    pub field: Option<String>,
+    /// H3 çözünürlüğü (0-15, varsayılan: 8)
+    pub resolution: Option<u8>,
+    /// Yalnızca bu zamandan sonra oluşturulan kayıtlar (dahil; kullanıcı katmanında `heatmap:read` kapsamı gerekir)
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub from: Option<DateTime<Utc>>,
+    /// Yalnızca bu zamandan önce oluşturulan kayıtlar (hariç; kullanıcı katmanında `heatmap:read` kapsamı gerekir)
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub to: Option<DateTime<Utc>>,
+    /// Gizlilik modu; kimliği doğrulanmamış veya `heatmap:read` kapsamı olmayan çağıranlar için her zaman açıktır
+    pub privacy: Option<bool>,
+    /// Eşiğin altındaki hücreler için işlem (varsayılan: konfigürasyon)
+    #[param(inline)]
+    pub suppression: Option<SuppressionMode>,
+    /// Laplace gürültüsü için epsilon (yalnızca `heatmap:read` kapsamıyla; varsayılan: konfigürasyon)
+    pub epsilon: Option<f64>,
+}
+
+// İstatistik modelleri
+
+/// Kayıt sayılarının gruplanacağı zaman aralığı
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SignupInterval {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl SignupInterval {
+    /// PostgreSQL `date_trunc` alan adı
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignupInterval::Day => "day",
+            SignupInterval::Week => "week",
+            SignupInterval::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StatsQuery {
+    /// Kayıtların gruplanacağı aralık (varsayılan: day)
+    #[param(inline)]
+    pub interval: Option<SignupInterval>,
+    /// Bugün dahil geriye doğru listelenecek aralık sayısı (varsayılan: 30, en fazla: 366)
+    pub periods: Option<i64>,
+    /// En kalabalık bölgeler için H3 çözünürlüğü (en fazla ve varsayılan: heatmap çözünürlüğü)
+    pub resolution: Option<u8>,
+    /// Listelenecek en kalabalık bölge sayısı (varsayılan: 10, en fazla: 100)
+    pub top: Option<usize>,
+}
+
+/// Bir aralıkta kaydolan kullanıcı sayısı
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignupCount {
+    /// Aralığın başlangıcı (UTC)
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub period_start: DateTime<Utc>,
+    pub count: i64,
+}
+
+/// Kullanıcı istatistikleri (silinmiş kullanıcılar hariç)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserStats {
+    pub total: i64,
+    /// Konumu olan kullanıcılar
+    pub with_location: i64,
+    /// Konumu olmayan (veya silinmiş) kullanıcılar
+    pub without_location: i64,
+    pub signup_interval: SignupInterval,
+    /// En eskiden en yeniye, boş aralıklar dahil kayıt sayıları
+    pub signups: Vec<SignupCount>,
+    /// `top_regions` için kullanılan H3 çözünürlüğü
+    pub region_resolution: u8,
+    /// Kullanıcı sayısına göre en kalabalık H3 hücreleri; `heatmap_min_count` altındaki hücreler heatmap'teki
+    /// gizleme moduyla gizlenir veya üst hücrelere taşınır, merkezler H3 hücre merkezidir
+    pub top_regions: Vec<H3HeatmapCell>,
+}
+
+/// Bir gruptaki liman sayısı
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PortCount {
+    /// Grup değeri (ülke veya liman tipi)
+    pub key: String,
+    pub count: i64,
+}
+
+/// Liman istatistikleri (silinmiş limanlar hariç)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PortStats {
+    pub total: i64,
+    /// Ülkelere göre liman sayıları (en kalabalık önce)
+    pub by_country: Vec<PortCount>,
+    /// Tiplere göre liman sayıları (en kalabalık önce)
+    pub by_type: Vec<PortCount>,
+    /// Kapasitesi bilinen limanlar
+    pub with_capacity: i64,
+    /// Toplam TEU kapasitesi
+    pub total_capacity_teu: i64,
+    /// Kapasitesi bilinen limanların ortalama TEU kapasitesi (hiç yoksa boş)
+    pub average_capacity_teu: Option<f64>,
+}
+
+// API anahtarı modelleri
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ApiKey {
+    /// API anahtarının benzersiz kimliği (UUID)
+    pub id: Uuid,
+    /// Anahtarın açıklayıcı adı (örn: "nightly-batch")
+    pub name: String,
+    /// Anahtarın görüntülenebilir ön eki
+    pub prefix: String,
+    /// Anahtarın yetki kapsamları
+    pub scopes: Vec<String>,
+    /// Anahtarın son geçerlilik tarihi (isteğe bağlı)
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Anahtarın son kullanılma tarihi
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Anahtarın iptal edilme tarihi
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Anahtarın oluşturulma tarihi
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKey {
+    /// Anahtarın açıklayıcı adı
+    #[schema(min_length = 1, max_length = 255, example = "nightly-batch")]
+    pub name: String,
+    /// Anahtara verilecek yetki kapsamları
+    #[schema(min_items = 1)]
+    pub scopes: Vec<Scope>,
+    /// Anahtarın son geçerlilik tarihi (isteğe bağlı, gelecekte olmalı)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2025-01-01T00:00:00Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyResponse {
+    /// API anahtarının string formatında kimliği
+    pub id: String,
+    /// Anahtarın açıklayıcı adı
+    pub name: String,
+    /// Anahtarın görüntülenebilir ön eki
+    pub prefix: String,
+    /// Anahtarın yetki kapsamları
+    pub scopes: Vec<Scope>,
+    /// Anahtarın son geçerlilik tarihi (isteğe bağlı)
+    #[schema(value_type = Option<String>, format = "date-time", example = "2025-01-01T00:00:00Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Anahtarın son kullanılma tarihi
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Anahtarın iptal edilme tarihi
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Anahtarın oluşturulma tarihi
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id.to_string(),
+            name: api_key.name,
+            prefix: api_key.prefix,
+            scopes: api_key.scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            revoked_at: api_key.revoked_at,
+            created_at: api_key.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    /// Tam API anahtarı; yalnızca oluşturma sırasında bir kez gösterilir
+    pub key: String,
+    /// Oluşturulan anahtarın bilgileri
+    pub api_key: ApiKeyResponse,
+}
+
+// Denetim kaydı modelleri
+
+/// Denetlenen varlık tipi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEntity {
+    User,
+    Port,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::User => "user",
+            AuditEntity::Port => "port",
+        }
+    }
+
+    /// Varlığın tutulduğu tablo
+    pub fn table(&self) -> &'static str {
+        match self {
+            AuditEntity::User => "users",
+            AuditEntity::Port => "ports",
+        }
+    }
+}
+
+/// Denetlenen işlem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    /// Saklama süresi dolan kaydın kalıcı olarak silinmesi
+    Purge,
+    /// GDPR silme talebiyle kişisel verilerin silinmesi
+    Erase,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Erase => "erase",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
+pub struct AuditLogEntry {
+    /// Kaydın benzersiz kimliği (UUID)
+    pub id: Uuid,
+    /// Değişikliğin zamanı
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub occurred_at: DateTime<Utc>,
+    /// Değişikliği yapan kimlik (örn: "api_key:<uuid>", "oidc:<sub>", "bootstrap", "system:purge")
+    pub actor: String,
+    /// İşlem ("create", "update", "delete", "restore", "purge", "erase")
+    pub action: String,
+    /// Varlık tipi ("user", "port")
+    pub entity_type: String,
+    /// Varlığın kimliği
+    pub entity_id: Uuid,
+    /// Değişiklikten önceki kayıt
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<serde_json::Value>,
+    /// Değişiklikten sonraki kayıt
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<serde_json::Value>,
+}
+
+/// Denetim kaydı sorgu filtreleri (hepsi isteğe bağlı)
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    /// Varlık tipi
+    #[param(inline)]
+    pub entity_type: Option<AuditEntity>,
+    /// Varlığın kimliği
+    pub entity_id: Option<Uuid>,
+    /// Değişikliği yapan kimlik (örn: "api_key:<uuid>")
+    pub actor: Option<String>,
+    /// Bu zamandan sonraki kayıtlar (dahil)
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub from: Option<DateTime<Utc>>,
+    /// Bu zamandan önceki kayıtlar (hariç)
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub to: Option<DateTime<Utc>>,
+    /// Döndürülecek en fazla kayıt sayısı (varsayılan: 100, en fazla: 1000)
+    pub limit: Option<i64>,
+}
+
+// H3 denetim modelleri
+
+/// H3 denetim işi başlatma talebi
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct H3AuditRequest {
+    /// true ise uyuşmayan kayıtların h3_index'i koordinatlardan yeniden hesaplanır (varsayılan: false, yalnızca rapor)
+    #[serde(default)]
+    pub fix: bool,
+    /// Tek transaction'da işlenecek kayıt sayısı (varsayılan: 500, en fazla: 10000)
+    #[schema(minimum = 1, maximum = 10000, example = 500)]
+    pub batch_size: Option<u32>,
+}
+
+/// Arka plan işinin durumu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Kayıtlı h3_index'i koordinatlarından hesaplanan index'le uyuşmayan kayıt
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct H3Mismatch {
+    /// Varlık tipi
+    pub entity: AuditEntity,
+    /// Kaydın kimliği
+    pub id: Uuid,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Kayıtlı index
+    pub stored: Option<String>,
+    /// Kayıtlı index'in çözünürlüğü (geçerli bir hücre değilse null)
+    pub stored_resolution: Option<u8>,
+    /// Koordinatlardan hesaplanan index (koordinat yoksa veya geçersizse null)
+    pub expected: Option<String>,
+    /// Düzeltildi mi; koordinatları geçersiz kayıtlar düzeltilemez
+    pub fixed: bool,
+}
+
+/// Bir varlık tipi için denetim sayaçları
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct H3AuditCounts {
+    /// Toplam kayıt sayısı (silinmişler dahil)
+    pub total: u64,
+    /// Kontrol edilen kayıt sayısı
+    pub checked: u64,
+    /// Uyuşmayan kayıt sayısı
+    pub mismatched: u64,
+    /// Düzeltilen kayıt sayısı
+    pub fixed: u64,
+}
+
+/// H3 denetim işinin durumu ve sonucu
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct H3AuditStatus {
+    /// İşin kimliği
+    pub id: Uuid,
+    pub state: JobState,
+    /// Uyuşmazlıklar düzeltiliyor mu
+    pub fix: bool,
+    /// Beklenen index'lerin hesaplandığı çözünürlük
+    pub resolution: u8,
+    pub batch_size: u32,
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub started_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub users: H3AuditCounts,
+    pub ports: H3AuditCounts,
+    /// Uyuşmayan kayıtlar (en fazla 1000 kayıt raporlanır)
+    pub mismatches: Vec<H3Mismatch>,
+    /// Uyuşmazlık listesi kırpıldıysa true
+    pub mismatches_truncated: bool,
+    /// İş başarısız olduysa hata mesajı
+    pub error: Option<String>,
+}
+
+// Liman etki alanı modelleri
+
+/// GeoJSON `MultiPolygon` geometrisi
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoJsonMultiPolygon {
+    /// Her zaman "MultiPolygon"
+    #[serde(rename = "type")]
+    #[schema(example = "MultiPolygon")]
+    pub kind: String,
+    /// `[polygon][halka][nokta][boylam, enlem]`; her polygonun ilk halkası dış sınır, sonrakiler deliklerdir
+    #[schema(value_type = Vec<Vec<Vec<Vec<f64>>>>)]
+    pub coordinates: MultiPolygonCoordinates,
+}
+
+impl GeoJsonMultiPolygon {
+    pub fn new(coordinates: MultiPolygonCoordinates) -> Self {
+        Self { kind: "MultiPolygon".to_string(), coordinates }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CatchmentQuery {
+    /// Yalnızca bu tipteki limanlar arasından atama yapılır (container, cruise, cargo, fishing, ferry; varsayılan: tümü)
+    pub port_type: Option<String>,
+    /// Kullanıcıların gruplandığı ve etki alanlarının oluşturulduğu H3 çözünürlüğü (en fazla ve varsayılan: konfigürasyon)
+    pub resolution: Option<u8>,
+    /// true ise önbellekteki sonuç güncel olsa bile yeniden hesaplanır
+    pub refresh: Option<bool>,
+}
+
+/// Bir limanın etki alanı: en yakın limanı bu liman olan kullanıcılar
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PortCatchment {
+    pub port_id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub port_type: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Limana atanan kullanıcı sayısı (1 ile `heatmap_min_count` arasındaysa boş)
+    pub user_count: Option<i64>,
+    /// Atanan kullanıcıların limana medyan uzaklığı (km; kullanıcı yoksa veya sayı gizlendiyse boş)
+    pub median_distance_km: Option<f64>,
+    /// Atanan en uzak kullanıcının limana uzaklığı (km; kullanıcı yoksa veya sayı gizlendiyse boş)
+    pub max_distance_km: Option<f64>,
+    /// Etki alanında çizilen H3 hücresi sayısı
+    pub cell_count: usize,
+    /// Atanan ve en az `heatmap_min_count` kullanıcısı olan H3 hücrelerinin birleşimi
+    pub catchment: GeoJsonMultiPolygon,
+}
+
+/// Liman etki alanı raporu
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CatchmentReport {
+    /// Atamada kullanılan liman tipi (tümü için boş)
+    pub port_type: Option<String>,
+    pub resolution: u8,
+    /// Raporun hesaplandığı zaman
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub computed_at: DateTime<Utc>,
+    /// Konumu olan kullanıcı sayısı
+    pub located_users: i64,
+    /// Limana atanamayan kullanıcılar (uygun liman yoksa)
+    pub unassigned_users: i64,
+    /// Kullanıcı sayısına göre sıralı limanlar (kullanıcısı olmayanlar dahil)
+    pub ports: Vec<PortCatchment>,
+}
+
+/// Hesaplanmakta olan etki alanı raporunun durumu
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CatchmentJob {
+    pub id: Uuid,
+    pub state: JobState,
+    pub port_type: Option<String>,
+    pub resolution: u8,
+    #[schema(value_type = String, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub started_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = "date-time", example = "2024-01-01T00:00:00Z")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Önceki hesaplamanın hatası (başarısız olduysa)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Liman kapsama boşluğu modelleri
+
+/// Kapsama boşluğu yanıt biçimi
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    #[default]
+    Json,
+    /// `application/geo+json` FeatureCollection
+    Geojson,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CoverageGapQuery {
+    /// Kullanıcıların gruplandığı H3 çözünürlüğü (varsayılan: etki alanı çözünürlüğü, en fazla: heatmap çözünürlüğü)
+    pub resolution: Option<u8>,
+    /// En yakın limanı bundan uzak olan hücreler boşluk sayılır (km, varsayılan: en yakın liman aramasının en fazla mesafesi)
+    pub max_distance_km: Option<f64>,
+    /// Yalnızca bu tipteki limanlar hizmet veriyor sayılır (varsayılan: tümü)
+    pub port_type: Option<String>,
+    /// Döndürülecek en fazla hücre sayısı (varsayılan: 100, en fazla: 1000)
+    pub limit: Option<usize>,
+    /// Yanıt biçimi (varsayılan: json)
+    #[param(inline)]
+    pub format: Option<CoverageFormat>,
+}
+
+/// İyi hizmet alamayan, kullanıcısı bulunan bir H3 hücresi
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CoverageGap {
+    pub h3_index: String,
+    /// Hücredeki kullanıcı sayısı
+    pub user_count: i64,
+    /// Hücre merkezinin enlemi
+    pub center_latitude: f64,
+    /// Hücre merkezinin boylamı
+    pub center_longitude: f64,
+    /// Hücre merkezine en yakın liman (hiç liman yoksa boş)
+    pub nearest_port_id: Option<Uuid>,
+    pub nearest_port_code: Option<String>,
+    /// Hücre merkezinin en yakın limana uzaklığı (km)
+    pub nearest_port_distance_km: Option<f64>,
+}
+
+/// Kapsama boşluğu analizi (JSON biçimi)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CoverageGapReport {
+    pub resolution: u8,
+    pub max_distance_km: f64,
+    pub port_type: Option<String>,
+    /// Konumu olan kullanıcı sayısı
+    pub located_users: i64,
+    /// Listelenen boşluk hücrelerindeki toplam kullanıcı sayısı
+    pub underserved_users: i64,
+    /// `heatmap_min_count`'tan az kullanıcısı olduğu için listelenmeyen boşluk hücrelerindeki toplam kullanıcı sayısı
+    pub suppressed_users: i64,
+    /// Listelenebilen boşluk hücresi sayısı (`limit` uygulanmadan önce)
+    pub total_cells: usize,
+    /// Kullanıcı sayısına göre sıralı boşluk hücreleri
+    pub cells: Vec<CoverageGap>,
+}
+
+/// Kapsama boşluğu hücresinin GeoJSON karşılığı
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CoverageGapFeature {
+    /// Her zaman "Feature"
+    #[serde(rename = "type")]
+    #[schema(example = "Feature")]
+    pub kind: String,
+    /// Hücrenin sınırı
+    pub geometry: GeoJsonMultiPolygon,
+    pub properties: CoverageGap,
+}
+
+/// Kapsama boşluğu analizi (GeoJSON biçimi)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CoverageGapCollection {
+    /// Her zaman "FeatureCollection"
+    #[serde(rename = "type")]
+    #[schema(example = "FeatureCollection")]
+    pub kind: String,
+    pub features: Vec<CoverageGapFeature>,
+}
+
+// Sağlık kontrolü modelleri
+
+/// Bir bağımlılığın veya servisin durumu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// Sağlıklı
+    Up,
+    /// Çalışıyor ama yeni istek kaldıramayabilir (örn: bağlantı havuzu dolu)
+    Degraded,
+    /// Kullanılamıyor
+    Down,
+}
+
+/// Veritabanı erişim kontrolü
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatabaseCheck {
+    pub status: HealthStatus,
+    /// `SELECT 1` sorgusunun süresi (milisaniye)
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Migrasyon durumu kontrolü
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MigrationCheck {
+    pub status: HealthStatus,
+    /// Uygulamada tanımlı migrasyon sayısı
+    pub expected: usize,
+    /// Veritabanında başarıyla uygulanmış migrasyon sayısı
+    pub applied: usize,
+    /// Henüz uygulanmamış migrasyon sürümleri
+    pub pending: Vec<i64>,
+    /// Yarıda kalmış veya içeriği değişmiş migrasyon sürümleri
+    pub failed: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Bağlantı havuzu kapasitesi kontrolü
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PoolCheck {
+    pub status: HealthStatus,
+    /// Açık bağlantı sayısı
+    pub size: u32,
+    /// Boştaki bağlantı sayısı
+    pub idle: u32,
+    /// En fazla bağlantı sayısı
+    pub max: u32,
+    /// Hemen alınabilecek bağlantı sayısı (boştakiler ve açılabilecek yeni bağlantılar)
+    pub available: u32,
+}
+
+/// Bağımlılık bazında hazır olma durumu
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessChecks {
+    pub database: DatabaseCheck,
+    pub migrations: MigrationCheck,
+    pub pool: PoolCheck,
+}
+
+/// Hazır olma (readiness) yanıtı; genel durum en kötü bağımlılığın durumudur
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub status: HealthStatus,
+    pub checks: ReadinessChecks,
 }
@@ -0,0 +1,435 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::Value;
+
+use crate::auth::{Principal, PrincipalKind, Scope};
+use crate::config::AppConfig;
+
+/// Bilinmeyen bir `kid` geldiğinde JWKS'i en fazla bu sıklıkla yeniden yükleriz
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// JWKS URL'inden yükleme için zaman aşımı
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum OidcError {
+    /// Token başlığı okunamadı veya `kid` eksik
+    Malformed(String),
+    /// RS256/ES256 dışında bir algoritma
+    UnsupportedAlgorithm(Algorithm),
+    /// JWKS içinde bulunamayan anahtar
+    UnknownKey(String),
+    /// İmza, süre, issuer veya audience doğrulaması başarısız
+    Invalid(jsonwebtoken::errors::Error),
+    /// JWKS dosyası/URL'i yüklenemedi
+    Jwks(String),
+}
+
+impl fmt::Display for OidcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OidcError::Malformed(e) => write!(f, "Token okunamadı: {}", e),
+            OidcError::UnsupportedAlgorithm(alg) => write!(f, "Desteklenmeyen algoritma: {:?}", alg),
+            OidcError::UnknownKey(kid) => write!(f, "Bilinmeyen imza anahtarı: {}", kid),
+            OidcError::Invalid(e) => write!(f, "Token doğrulanamadı: {}", e),
+            OidcError::Jwks(e) => write!(f, "JWKS yüklenemedi: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OidcError {}
+
+/// JWKS kaynağı: yerel dosya veya HTTP(S) URL'i
+#[derive(Debug, Clone)]
+pub enum JwksSource {
+    File(PathBuf),
+    Url(String),
+}
+
+struct CachedKeys {
+    keys: HashMap<String, (Algorithm, DecodingKey)>,
+    fetched_at: Instant,
+}
+
+/// JWKS önbelleği
+///
+/// Anahtarlar `refresh_interval` süresince önbellekte tutulur. Önbellekte olmayan bir
+/// `kid` geldiğinde (anahtar rotasyonu) kaynak yeniden yüklenir; yenileme başarısız olursa
+/// eski anahtarlarla devam edilir. Aynı anda tek yenileme çalışır; bekleyen istekler kilidi aldıktan sonra
+/// önbelleğe yeniden bakar, böylece aynı bilinmeyen `kid` ile gelen eşzamanlı istekler tek yükleme yapar.
+pub struct JwksCache {
+    source: JwksSource,
+    client: reqwest::Client,
+    refresh_interval: Duration,
+    min_refresh_interval: Duration,
+    state: RwLock<Option<CachedKeys>>,
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl JwksCache {
+    pub fn new(source: JwksSource, refresh_interval: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(JWKS_FETCH_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            source,
+            client,
+            refresh_interval,
+            min_refresh_interval: MIN_REFRESH_INTERVAL,
+            state: RwLock::new(None),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    async fn load(&self) -> Result<JwkSet, OidcError> {
+        match &self.source {
+            JwksSource::File(path) => {
+                let content = tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(|e| OidcError::Jwks(format!("{}: {}", path.display(), e)))?;
+                serde_json::from_str(&content).map_err(|e| OidcError::Jwks(e.to_string()))
+            }
+            JwksSource::Url(url) => {
+                let response = self
+                    .client
+                    .get(url)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| OidcError::Jwks(e.to_string()))?;
+                response.json().await.map_err(|e| OidcError::Jwks(e.to_string()))
+            }
+        }
+    }
+
+    /// JWKS'i kaynaktan yeniden yükler ve önbelleği değiştirir
+    pub async fn refresh(&self) -> Result<usize, OidcError> {
+        let _guard = self.refresh_lock.lock().await;
+        self.reload().await
+    }
+
+    // Yenileme kilidi alınmışken çağrılmalıdır
+    async fn reload(&self) -> Result<usize, OidcError> {
+        let jwks = self.load().await?;
+
+        let keys: HashMap<_, _> = jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone()?;
+                let algorithm = key_algorithm(jwk)?;
+                let key = DecodingKey::from_jwk(jwk).ok()?;
+                Some((kid, (algorithm, key)))
+            })
+            .collect();
+
+        let count = keys.len();
+        *self.state.write().unwrap() = Some(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+        });
+        Ok(count)
+    }
+
+    fn cached(&self, kid: &str) -> (Option<(Algorithm, DecodingKey)>, Option<Duration>) {
+        let state = self.state.read().unwrap();
+        match state.as_ref() {
+            Some(cached) => (cached.keys.get(kid).cloned(), Some(cached.fetched_at.elapsed())),
+            None => (None, None),
+        }
+    }
+
+    // Önbellekteki anahtar ve yaşına göre JWKS'in yeniden yüklenmesi gerekiyor mu
+    fn should_refresh(&self, key: &Option<(Algorithm, DecodingKey)>, age: Option<Duration>) -> bool {
+        match (key, age) {
+            (_, None) => true,
+            (Some(_), Some(age)) => age >= self.refresh_interval,
+            (None, Some(age)) => age >= self.min_refresh_interval,
+        }
+    }
+
+    /// `kid` için imza anahtarını döndürür; gerekirse JWKS'i yeniler
+    pub async fn get_key(&self, kid: &str) -> Result<(Algorithm, DecodingKey), OidcError> {
+        let (key, age) = self.cached(kid);
+        if !self.should_refresh(&key, age) {
+            return key.ok_or_else(|| OidcError::UnknownKey(kid.to_string()));
+        }
+
+        // Kilidi beklerken başka bir istek JWKS'i yenilemiş olabilir
+        let _guard = self.refresh_lock.lock().await;
+        let (key, age) = self.cached(kid);
+        if self.should_refresh(&key, age) {
+            if let Err(e) = self.reload().await {
+                if key.is_none() {
+                    return Err(e);
+                }
+                tracing::warn!(error = %e, "JWKS yenilenemedi, önbellekteki anahtarlar kullanılıyor");
+            }
+            if let (Some(key), _) = self.cached(kid) {
+                return Ok(key);
+            }
+        }
+
+        key.ok_or_else(|| OidcError::UnknownKey(kid.to_string()))
+    }
+}
+
+/// JWK için kullanılacak algoritmayı belirler; yalnızca RS256 ve ES256 kabul edilir
+fn key_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    match (jwk.common.key_algorithm, &jwk.algorithm) {
+        (Some(KeyAlgorithm::RS256), AlgorithmParameters::RSA(_)) => Some(Algorithm::RS256),
+        (Some(KeyAlgorithm::ES256), AlgorithmParameters::EllipticCurve(_)) => Some(Algorithm::ES256),
+        (None, AlgorithmParameters::RSA(_)) => Some(Algorithm::RS256),
+        (None, AlgorithmParameters::EllipticCurve(params)) if params.curve == EllipticCurve::P256 => {
+            Some(Algorithm::ES256)
+        }
+        _ => None,
+    }
+}
+
+/// `rol=kapsam1,kapsam2;rol2=kapsam3` formatındaki eşlemeyi çözümler
+pub fn parse_role_mapping(raw: &str) -> Result<HashMap<String, Vec<Scope>>, String> {
+    let mut mapping = HashMap::new();
+
+    for entry in raw.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (role, scopes) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Geçersiz rol eşlemesi: '{}' (beklenen: rol=kapsam1,kapsam2)", entry))?;
+        let scopes = scopes
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Scope>, _>>()?;
+        mapping.insert(role.trim().to_string(), scopes);
+    }
+
+    Ok(mapping)
+}
+
+/// Noktalı yol ile claim getirir (örn: `realm_access.roles`)
+fn claim_at<'a>(claims: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(claims, |value, key| value.get(key))
+}
+
+/// Rol claim'i dizi veya boşlukla ayrılmış string olabilir
+fn roles_from_claim(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        Some(Value::String(s)) => s.split_whitespace().map(str::to_string).collect(),
+        _ => vec![],
+    }
+}
+
+/// Şirket SSO'su tarafından verilen OIDC bearer token'larını doğrular
+pub struct OidcValidator {
+    issuer: String,
+    audience: String,
+    roles_claim: String,
+    role_mapping: HashMap<String, Vec<Scope>>,
+    jwks: JwksCache,
+}
+
+impl OidcValidator {
+    pub fn new(
+        issuer: String,
+        audience: String,
+        roles_claim: String,
+        role_mapping: HashMap<String, Vec<Scope>>,
+        jwks: JwksCache,
+    ) -> Self {
+        Self { issuer, audience, roles_claim, role_mapping, jwks }
+    }
+
+    /// Konfigürasyondan doğrulayıcı oluşturur; OIDC yapılandırılmamışsa `None` döner
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>, String> {
+        let config = &config.auth.oidc;
+        let Some(issuer) = config.issuer.clone() else {
+            return Ok(None);
+        };
+        let audience = config
+            .audience
+            .clone()
+            .ok_or("OIDC_ISSUER tanımlıysa OIDC_AUDIENCE da tanımlanmalı")?;
+        let source = match (&config.jwks_url, &config.jwks_path) {
+            (Some(url), _) => JwksSource::Url(url.clone()),
+            (None, Some(path)) => JwksSource::File(PathBuf::from(path)),
+            (None, None) => return Err("OIDC_JWKS_URL veya OIDC_JWKS_PATH tanımlanmalı".to_string()),
+        };
+        let role_mapping = parse_role_mapping(&config.role_mapping)?;
+
+        Ok(Some(Self::new(
+            issuer,
+            audience,
+            config.roles_claim.clone(),
+            role_mapping,
+            JwksCache::new(source, Duration::from_secs(config.jwks_refresh_secs)),
+        )))
+    }
+
+    pub fn jwks(&self) -> &JwksCache {
+        &self.jwks
+    }
+
+    /// Token'ı doğrular ve claim'lerdeki rolleri servis kapsamlarına eşler
+    pub async fn validate(&self, token: &str) -> Result<Principal, OidcError> {
+        let header = decode_header(token).map_err(|e| OidcError::Malformed(e.to_string()))?;
+        if !matches!(header.alg, Algorithm::RS256 | Algorithm::ES256) {
+            return Err(OidcError::UnsupportedAlgorithm(header.alg));
+        }
+        let kid = header.kid.ok_or_else(|| OidcError::Malformed("kid eksik".to_string()))?;
+
+        let (algorithm, key) = self.jwks.get_key(&kid).await?;
+        if algorithm != header.alg {
+            return Err(OidcError::UnsupportedAlgorithm(header.alg));
+        }
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<Value>(token, &key, &validation)
+            .map_err(OidcError::Invalid)?
+            .claims;
+
+        let subject = claims["sub"].as_str().unwrap_or_default().to_string();
+        let name = ["preferred_username", "email"]
+            .iter()
+            .find_map(|claim| claims[*claim].as_str())
+            .unwrap_or(&subject)
+            .to_string();
+
+        let scopes: HashSet<Scope> = roles_from_claim(claim_at(&claims, &self.roles_claim))
+            .iter()
+            .filter_map(|role| self.role_mapping.get(role))
+            .flatten()
+            .copied()
+            .collect();
+
+        Ok(Principal {
+            kind: PrincipalKind::Oidc(subject),
+            name,
+            scopes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    // testdata/oidc altındaki anahtarlar yalnızca testler için üretilmiştir
+    const RSA_PRIVATE: &[u8] = include_bytes!("../testdata/oidc/rsa_private.pem");
+    const EC_PRIVATE: &[u8] = include_bytes!("../testdata/oidc/ec_private.pem");
+    const JWKS: &str = include_str!("../testdata/oidc/jwks.json");
+
+    const ISSUER: &str = "https://sso.example.com/realms/dfds";
+    const AUDIENCE: &str = "rustmicro";
+
+    fn validator(jwks_path: PathBuf) -> OidcValidator {
+        let mut jwks = JwksCache::new(JwksSource::File(jwks_path), Duration::from_secs(3600));
+        jwks.min_refresh_interval = Duration::ZERO;
+        OidcValidator::new(
+            ISSUER.to_string(),
+            AUDIENCE.to_string(),
+            "realm_access.roles".to_string(),
+            parse_role_mapping("planner=ports:read,heatmap:read;ops=admin").unwrap(),
+            jwks,
+        )
+    }
+
+    fn fixture_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/oidc/jwks.json")
+    }
+
+    fn claims(roles: &[&str]) -> Value {
+        json!({
+            "iss": ISSUER,
+            "aud": AUDIENCE,
+            "sub": "user-123",
+            "preferred_username": "ayse",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "realm_access": { "roles": roles },
+        })
+    }
+
+    fn sign(alg: Algorithm, kid: &str, claims: &Value) -> String {
+        let key = match alg {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(RSA_PRIVATE).unwrap(),
+            _ => EncodingKey::from_ec_pem(EC_PRIVATE).unwrap(),
+        };
+        let mut header = Header::new(alg);
+        header.kid = Some(kid.to_string());
+        encode(&header, claims, &key).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_rs256_token_maps_roles_to_scopes() {
+        let validator = validator(fixture_path());
+        let token = sign(Algorithm::RS256, "test-rsa", &claims(&["planner", "unknown"]));
+
+        let principal = validator.validate(&token).await.unwrap();
+        assert_eq!(principal.kind, PrincipalKind::Oidc("user-123".to_string()));
+        assert_eq!(principal.name, "ayse");
+        assert_eq!(principal.scopes, HashSet::from([Scope::PortsRead, Scope::HeatmapRead]));
+    }
+
+    #[actix_web::test]
+    async fn test_es256_token_is_accepted() {
+        let validator = validator(fixture_path());
+        let token = sign(Algorithm::ES256, "test-ec", &claims(&["ops"]));
+
+        let principal = validator.validate(&token).await.unwrap();
+        assert!(principal.has_scope(Scope::UsersWrite));
+    }
+
+    #[actix_web::test]
+    async fn test_rejects_wrong_audience_and_expired_tokens() {
+        let validator = validator(fixture_path());
+
+        let mut wrong_audience = claims(&["ops"]);
+        wrong_audience["aud"] = json!("another-service");
+        let token = sign(Algorithm::RS256, "test-rsa", &wrong_audience);
+        assert!(matches!(validator.validate(&token).await, Err(OidcError::Invalid(_))));
+
+        let mut expired = claims(&["ops"]);
+        expired["exp"] = json!(chrono::Utc::now().timestamp() - 3600);
+        let token = sign(Algorithm::RS256, "test-rsa", &expired);
+        assert!(matches!(validator.validate(&token).await, Err(OidcError::Invalid(_))));
+    }
+
+    #[actix_web::test]
+    async fn test_unknown_kid_triggers_jwks_reload() {
+        // Başlangıçta yalnızca RSA anahtarını içeren bir JWKS, ardından rotasyonla EC anahtarı eklenir
+        let path = std::env::temp_dir().join(format!("jwks-{}.json", uuid::Uuid::new_v4()));
+        let mut jwks: Value = serde_json::from_str(JWKS).unwrap();
+        let ec_key = jwks["keys"].as_array_mut().unwrap().pop().unwrap();
+        std::fs::write(&path, jwks.to_string()).unwrap();
+
+        let validator = validator(path.clone());
+        let token = sign(Algorithm::ES256, "test-ec", &claims(&["ops"]));
+        assert!(matches!(validator.validate(&token).await, Err(OidcError::UnknownKey(_))));
+
+        jwks["keys"].as_array_mut().unwrap().push(ec_key);
+        std::fs::write(&path, jwks.to_string()).unwrap();
+        assert!(validator.validate(&token).await.is_ok());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_parse_role_mapping_rejects_unknown_scope() {
+        assert!(parse_role_mapping("viewer=users:read").is_ok());
+        assert!(parse_role_mapping("viewer=users:delete").is_err());
+        assert!(parse_role_mapping("viewer").is_err());
+    }
+}
@@ -0,0 +1,434 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Üst servisten gelen W3C trace bağlamının taşındığı başlık (ve span alanı)
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Dışa aktarılmayı bekleyen en fazla span; kuyruk doluysa yeni span'lar düşürülür
+const QUEUE_CAPACITY: usize = 2048;
+
+/// Tek istekte gönderilecek en fazla span
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Biriken span'ların gönderilme aralığı
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Collector isteği için zaman aşımı
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// W3C `traceparent` başlığından okunan uzak trace bağlamı
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// `00-<32 hex trace id>-<16 hex span id>-<2 hex flags>` biçimini çözer; geçersiz başlıklar yok sayılır
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+
+        // Bilinmeyen sürümlerde ek alanlar olabilir; 00 sürümünde olamaz, ff ise geçersizdir
+        if version.len() != 2 || version.eq_ignore_ascii_case("ff") || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        u8::from_str_radix(version, 16).ok()?;
+
+        let trace_id: [u8; 16] = decode_hex(trace_id)?;
+        let span_id: [u8; 8] = decode_hex(span_id)?;
+        let [flags]: [u8; 1] = decode_hex(flags)?;
+        if trace_id == [0; 16] || span_id == [0; 8] {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            span_id,
+            sampled: flags & 0x01 == 0x01,
+        })
+    }
+}
+
+/// Sabit uzunlukta küçük harfli hex dizgesini çözer (W3C büyük harfe izin vermez)
+fn decode_hex<const N: usize>(value: &str) -> Option<[u8; N]> {
+    if value.len() != N * 2 || value.bytes().any(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    hex::decode(value).ok()?.try_into().ok()
+}
+
+/// Kök span'lar için örnekleme kararı; trace id'nin son 8 byte'ı oranla karşılaştırılır,
+/// böylece aynı trace için karar her serviste aynı çıkar
+pub fn should_sample(trace_id: &[u8; 16], ratio: f64) -> bool {
+    if ratio >= 1.0 {
+        return true;
+    }
+    if ratio <= 0.0 || ratio.is_nan() {
+        return false;
+    }
+    let value = u64::from_be_bytes(trace_id[8..].try_into().expect("8 byte"));
+    (value as f64) < ratio * u64::MAX as f64
+}
+
+/// OTLP span türü (`otel.kind` alanıyla belirtilir)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanKind {
+    Internal = 1,
+    Server = 2,
+    Client = 3,
+}
+
+impl SpanKind {
+    fn parse(value: &str) -> Self {
+        match value {
+            "server" => SpanKind::Server,
+            "client" => SpanKind::Client,
+            _ => SpanKind::Internal,
+        }
+    }
+}
+
+/// Span özniteliği; OTLP JSON'daki `AnyValue` karşılığı
+#[derive(Debug, Clone, PartialEq)]
+enum AttributeValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+impl AttributeValue {
+    fn to_otlp(&self) -> Value {
+        match self {
+            AttributeValue::String(v) => json!({ "stringValue": v }),
+            // OTLP JSON'da 64 bit tamsayılar metin olarak kodlanır
+            AttributeValue::Int(v) => json!({ "intValue": v.to_string() }),
+            AttributeValue::Double(v) => json!({ "doubleValue": v }),
+            AttributeValue::Bool(v) => json!({ "boolValue": v }),
+        }
+    }
+}
+
+/// Span alanlarını öznitelik listesine çeviren visitor; `otel.*` ve `traceparent` alanları özel ele alınır
+#[derive(Default)]
+struct AttributeVisitor {
+    name: Option<String>,
+    kind: Option<SpanKind>,
+    traceparent: Option<String>,
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl AttributeVisitor {
+    fn set(&mut self, field: &Field, value: AttributeValue) {
+        match (field.name(), value) {
+            ("otel.name", AttributeValue::String(name)) => self.name = Some(name),
+            ("otel.kind", AttributeValue::String(kind)) => self.kind = Some(SpanKind::parse(&kind)),
+            (TRACEPARENT_HEADER, AttributeValue::String(value)) => self.traceparent = Some(value),
+            (key, value) => {
+                self.attributes.retain(|(existing, _)| existing != key);
+                self.attributes.push((key.to_string(), value));
+            }
+        }
+    }
+}
+
+impl Visit for AttributeVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, AttributeValue::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, AttributeValue::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, AttributeValue::Int(i64::try_from(value).unwrap_or(i64::MAX)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, AttributeValue::Double(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, AttributeValue::Bool(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, AttributeValue::String(format!("{:?}", value)));
+    }
+}
+
+/// Açık bir span'ın durumu; span'ın registry uzantılarında tutulur
+#[derive(Debug, Clone)]
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    sampled: bool,
+    name: String,
+    kind: SpanKind,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, AttributeValue)>,
+    error: bool,
+}
+
+impl SpanData {
+    fn to_otlp(&self) -> Value {
+        let mut span = json!({
+            "traceId": hex::encode(self.trace_id),
+            "spanId": hex::encode(self.span_id),
+            "name": self.name,
+            "kind": self.kind as u8,
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(self.end),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": value.to_otlp() }))
+                .collect::<Vec<_>>(),
+            // 1: OK, 2: ERROR
+            "status": { "code": if self.error { 2 } else { 1 } },
+        });
+        if let Some(parent) = self.parent_span_id {
+            span["parentSpanId"] = json!(hex::encode(parent));
+        }
+        span
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+}
+
+/// Sıfırdan farklı rastgele bir kimlik üretir (W3C tamamen sıfır kimliği geçersiz sayar)
+fn random_id<const N: usize>() -> [u8; N] {
+    loop {
+        let mut id = [0u8; N];
+        rand::Rng::fill(&mut rand::thread_rng(), &mut id[..]);
+        if id != [0; N] {
+            return id;
+        }
+    }
+}
+
+/// Kapanan span'ları OTLP exporter'ına ileten tracing katmanı
+///
+/// Kök span'lar `traceparent` alanı taşıyorsa uzak trace'in devamı olur ve üst servisin örnekleme kararına
+/// uyar; aksi halde yeni bir trace başlatılır ve `sampling_ratio` ile örneklenir. Alt span'lar kararı
+/// ebeveynlerinden devralır.
+pub struct OtlpLayer {
+    sender: mpsc::Sender<SpanData>,
+    sampling_ratio: f64,
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = AttributeVisitor::default();
+        attrs.record(&mut visitor);
+
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|data| (data.trace_id, data.span_id, data.sampled))
+        });
+        let (trace_id, parent_span_id, sampled) = match parent {
+            Some((trace_id, span_id, sampled)) => (trace_id, Some(span_id), sampled),
+            None => match visitor.traceparent.as_deref().and_then(TraceContext::parse) {
+                Some(remote) => (remote.trace_id, Some(remote.span_id), remote.sampled),
+                None => {
+                    let trace_id = random_id();
+                    (trace_id, None, should_sample(&trace_id, self.sampling_ratio))
+                }
+            },
+        };
+
+        let now = SystemTime::now();
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id: random_id(),
+            parent_span_id,
+            sampled,
+            name: visitor.name.unwrap_or_else(|| span.name().to_string()),
+            kind: visitor.kind.unwrap_or(SpanKind::Internal),
+            start: now,
+            end: now,
+            attributes: visitor.attributes,
+            error: false,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        let Some(data) = extensions.get_mut::<SpanData>() else { return };
+
+        let mut visitor = AttributeVisitor {
+            attributes: std::mem::take(&mut data.attributes),
+            ..Default::default()
+        };
+        values.record(&mut visitor);
+        data.attributes = visitor.attributes;
+        if let Some(name) = visitor.name {
+            data.name = name;
+        }
+        if let Some(kind) = visitor.kind {
+            data.kind = kind;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Span içinde yazılan hata logları span'ı hatalı olarak işaretler
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        if let Some(span) = ctx.event_span(event) {
+            if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+                data.error = true;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(mut data) = span.extensions_mut().remove::<SpanData>() else { return };
+        if !data.sampled {
+            return;
+        }
+        data.end = SystemTime::now();
+        // Kuyruk doluysa (collector yavaş veya erişilemez) span düşürülür; istek yolu asla beklemez
+        let _ = self.sender.try_send(data);
+    }
+}
+
+/// OTLP/HTTP (JSON) exporter'ını başlatır ve span'ları ona ileten katmanı döndürür
+///
+/// `endpoint` collector'ın temel adresidir (örn: `http://otel-collector:4318`); `/v1/traces` eklenir.
+pub fn init(endpoint: &str, service_name: &str, sampling_ratio: f64) -> OtlpLayer {
+    let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+    let endpoint = endpoint.trim_end_matches('/');
+    let url = if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    };
+    actix_web::rt::spawn(export_spans(receiver, url, service_name.to_string()));
+
+    OtlpLayer { sender, sampling_ratio }
+}
+
+async fn export_spans(mut receiver: mpsc::Receiver<SpanData>, url: String, service_name: String) {
+    let client = reqwest::Client::builder()
+        .timeout(EXPORT_TIMEOUT)
+        .build()
+        .expect("HTTP istemcisi oluşturulamadı");
+    let mut ticker = tokio::time::interval(EXPORT_INTERVAL);
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+
+    loop {
+        let closed = tokio::select! {
+            span = receiver.recv() => match span {
+                Some(span) => {
+                    batch.push(span);
+                    if batch.len() < MAX_BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            let body = export_request(&service_name, &batch);
+            batch.clear();
+            match client.post(&url).json(&body).send().await {
+                Ok(res) if res.status().is_success() => {}
+                Ok(res) => tracing::warn!(status = res.status().as_u16(), "OTLP collector span'ları reddetti"),
+                Err(e) => tracing::warn!(error = %e, "Span'lar OTLP collector'a gönderilemedi"),
+            }
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+/// OTLP `ExportTraceServiceRequest` gövdesini oluşturur
+fn export_request(service_name: &str, spans: &[SpanData]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": env!("CARGO_PKG_NAME") },
+                "spans": spans.iter().map(SpanData::to_otlp).collect::<Vec<_>>(),
+            }]
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_parsing() {
+        let context = TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(hex::encode(context.trace_id), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(hex::encode(context.span_id), "00f067aa0ba902b7");
+        assert!(context.sampled);
+        assert!(!TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00").unwrap().sampled);
+
+        // Gelecek sürümlerde ek alanlar olabilir
+        assert!(TraceContext::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-ek").is_some());
+
+        for invalid in [
+            "",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-ek",
+        ] {
+            assert!(TraceContext::parse(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_sampling_ratio() {
+        let low = [0u8; 16];
+        let high = [0xff; 16];
+        assert!(should_sample(&high, 1.0));
+        assert!(!should_sample(&low, 0.0));
+        assert!(should_sample(&low, 0.5));
+        assert!(!should_sample(&high, 0.5));
+
+        let sampled = (0..1000).filter(|_| should_sample(&random_id(), 0.25)).count();
+        assert!((150..350).contains(&sampled), "{}", sampled);
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use h3o::CellIndex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::h3_utils::h3_to_coords;
+use crate::models::H3HeatmapCell;
+
+/// Gizlilik bütçesinin yenilendiği pencere
+pub const BUDGET_WINDOW_HOURS: i64 = 24;
+
+/// Eşiğin altında kalan hücrelerin ne yapılacağı
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SuppressionMode {
+    /// Hücreyi yanıttan çıkar
+    Suppress,
+    /// Hücreyi eşiğe ulaşana kadar üst hücrelerle birleştir
+    Rollup,
+}
+
+impl FromStr for SuppressionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "suppress" => Ok(SuppressionMode::Suppress),
+            "rollup" => Ok(SuppressionMode::Rollup),
+            _ => Err(format!("Bilinmeyen gizleme modu: {} (suppress veya rollup olmalı)", s)),
+        }
+    }
+}
+
+/// k-anonimlik sonucu: yayınlanabilir hücreler ve gizlenen kullanıcı sayısı
+#[derive(Debug, Default)]
+pub struct AnonymizedCells {
+    pub cells: Vec<(CellIndex, i64)>,
+    pub suppressed_users: i64,
+}
+
+/// `min_count`'un altındaki hücreleri gizler veya üst hücrelerde birleştirir
+///
+/// Birleştirmede yalnızca eşiğin altındaki hücreler üst hücreye taşınır; eşiği geçen kardeş hücreler
+/// kendi çözünürlüklerinde kalır. Çözünürlük 0'da hâlâ eşiğin altında kalan sayılar gizlenir.
+pub fn apply_k_anonymity(counts: HashMap<CellIndex, i64>, min_count: i64, mode: SuppressionMode) -> AnonymizedCells {
+    let mut result = AnonymizedCells::default();
+    let mut pending = counts;
+
+    loop {
+        let mut parents: HashMap<CellIndex, i64> = HashMap::new();
+        for (cell, count) in pending {
+            if count >= min_count {
+                result.cells.push((cell, count));
+                continue;
+            }
+            let parent = cell.resolution().pred().and_then(|resolution| cell.parent(resolution));
+            match (mode, parent) {
+                (SuppressionMode::Rollup, Some(parent)) => *parents.entry(parent).or_default() += count,
+                _ => result.suppressed_users += count,
+            }
+        }
+        if parents.is_empty() {
+            break;
+        }
+        pending = parents;
+    }
+
+    result
+}
+
+/// Ölçeği `scale` olan Laplace dağılımından örnek
+pub fn laplace_noise(scale: f64, rng: &mut impl Rng) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+/// Sayıya Laplace gürültüsü ekler; her kullanıcı tek bir hücrede sayıldığı için duyarlılık 1'dir
+pub fn noisy_count(count: i64, epsilon: f64, rng: &mut impl Rng) -> i64 {
+    let noisy = count as f64 + laplace_noise(1.0 / epsilon, rng);
+    noisy.round().max(0.0) as i64
+}
+
+/// Tam sayımlı kullanıcı heatmap hücrelerini (isteğe bağlı) gürültü ve k-anonimlik uygulanmış hücrelere dönüştürür
+///
+/// Gürültü eşikten önce eklenir ve eşik gürültülü sayımlara uygulanır; böylece bir hücrenin yanıtta yer alıp
+/// almaması gerçek sayımın deterministik bir fonksiyonu olmaz. Birleştirmede üst hücreye gürültülü sayımlar
+/// taşınır. Hücre merkezleri kullanıcı koordinatlarının ortalaması yerine H3 hücre merkezidir; böylece tek
+/// kişilik bir hücrenin merkezi o kişinin konumunu ele vermez. Dönen ikinci değer gizlenen kullanıcı sayısıdır
+/// (gürültü varsa gürültülü sayımlardan hesaplanır).
+pub fn anonymize_cells(
+    exact: Vec<H3HeatmapCell>,
+    min_count: i64,
+    mode: SuppressionMode,
+    epsilon: Option<f64>,
+    rng: &mut impl Rng,
+) -> (Vec<H3HeatmapCell>, i64) {
+    let counts: HashMap<CellIndex, i64> = exact
+        .into_iter()
+        .filter_map(|cell| {
+            let index = cell.h3_index.parse::<CellIndex>().ok()?;
+            let count = match epsilon {
+                Some(epsilon) => noisy_count(cell.count, epsilon, rng),
+                None => cell.count,
+            };
+            Some((index, count))
+        })
+        .collect();
+    let anonymized = apply_k_anonymity(counts, min_count, mode);
+
+    let mut cells: Vec<H3HeatmapCell> = anonymized
+        .cells
+        .into_iter()
+        .filter_map(|(index, count)| {
+            let h3_index = index.to_string();
+            let (center_latitude, center_longitude) = h3_to_coords(&h3_index).ok()?;
+            (count > 0).then_some(H3HeatmapCell {
+                h3_index,
+                count,
+                value: count as f64,
+                user_count: Some(count),
+                center_latitude,
+                center_longitude,
+            })
+        })
+        .collect();
+    cells.sort_by_key(|cell| std::cmp::Reverse(cell.count));
+
+    (cells, anonymized.suppressed_users)
+}
+
+/// İstatistiklerdeki en kalabalık bölgeler: gürültüsüz k-anonimlik uygulanmış hücrelerin en kalabalık `top` tanesi
+///
+/// Bu liste bütçe harcanmadan döndüğü için gürültü eklenmez; eşiğin altındaki hücreler heatmap'tekiyle aynı
+/// modla gizlenir veya üst hücrelere taşınır.
+pub fn top_regions(exact: Vec<H3HeatmapCell>, min_count: i64, mode: SuppressionMode, top: usize) -> Vec<H3HeatmapCell> {
+    let (mut cells, _) = anonymize_cells(exact, min_count, mode, None, &mut rand::thread_rng());
+    cells.truncate(top);
+    cells
+}
+
+/// Çağıran başına harcanan gizlilik bütçesi (epsilon); her pencere başında sıfırlanır
+pub struct PrivacyBudget {
+    limit: f64,
+    spent: Mutex<HashMap<String, (DateTime<Utc>, f64)>>,
+}
+
+impl PrivacyBudget {
+    pub fn new(limit: f64) -> Self {
+        Self {
+            limit,
+            spent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bütçeden `epsilon` harcar ve kalan bütçeyi döndürür; yetmiyorsa hiçbir şey harcamaz
+    pub fn try_spend(&self, caller: &str, epsilon: f64) -> Result<f64, f64> {
+        let now = Utc::now();
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Süresi dolmuş pencereleri temizle
+        spent.retain(|_, (window_start, _)| now - *window_start < Duration::hours(BUDGET_WINDOW_HOURS));
+
+        let (_, used) = spent.entry(caller.to_string()).or_insert((now, 0.0));
+        let remaining = self.limit - *used;
+        if epsilon > remaining + f64::EPSILON {
+            return Err(remaining.max(0.0));
+        }
+        *used += epsilon;
+        Ok((remaining - epsilon).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use h3o::{LatLng, Resolution};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn cell(lat: f64, lng: f64) -> CellIndex {
+        LatLng::new(lat, lng).unwrap().to_cell(Resolution::Nine)
+    }
+
+    #[test]
+    fn test_k_anonymity_modes() {
+        let dense = cell(41.0082, 28.9784);
+        let sparse = dense.grid_disk::<Vec<_>>(1).into_iter().find(|c| *c != dense).unwrap();
+        let counts = HashMap::from([(dense, 10), (sparse, 2)]);
+
+        let suppressed = apply_k_anonymity(counts.clone(), 5, SuppressionMode::Suppress);
+        assert_eq!(suppressed.cells, vec![(dense, 10)]);
+        assert_eq!(suppressed.suppressed_users, 2);
+
+        // Tek başına kalan 2 kullanıcı hiçbir çözünürlükte eşiğe ulaşamaz
+        let rolled = apply_k_anonymity(counts, 5, SuppressionMode::Rollup);
+        assert_eq!(rolled.cells, vec![(dense, 10)]);
+        assert_eq!(rolled.suppressed_users, 2);
+
+        let neighbours = HashMap::from([(dense, 3), (sparse, 3)]);
+        let rolled = apply_k_anonymity(neighbours, 5, SuppressionMode::Rollup);
+        assert_eq!(rolled.suppressed_users, 0);
+        assert_eq!(rolled.cells.len(), 1);
+        assert_eq!(rolled.cells[0].1, 6);
+        assert!(rolled.cells[0].0.resolution() < Resolution::Nine);
+    }
+
+    #[test]
+    fn test_noise_is_calibrated() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f64> = (0..20_000).map(|_| laplace_noise(2.0, &mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let mean_abs = samples.iter().map(|x| x.abs()).sum::<f64>() / samples.len() as f64;
+        // Laplace(0, b) için ortalama 0, ortalama mutlak sapma b'dir
+        assert!(mean.abs() < 0.1);
+        assert!((mean_abs - 2.0).abs() < 0.1);
+        assert!(noisy_count(0, 1.0, &mut rng) >= 0);
+    }
+
+    #[test]
+    fn test_threshold_applies_to_noisy_counts() {
+        let index = cell(41.0082, 28.9784);
+        let exact = |count| {
+            let h3_index = index.to_string();
+            vec![H3HeatmapCell { h3_index, count, value: count as f64, user_count: Some(count), center_latitude: 0.0, center_longitude: 0.0 }]
+        };
+        let mut rng = StdRng::seed_from_u64(11);
+        let published = |count, rng: &mut StdRng| {
+            (0..200)
+                .filter(|_| !anonymize_cells(exact(count), 5, SuppressionMode::Suppress, Some(0.5), rng).0.is_empty())
+                .count()
+        };
+
+        // Eşiğin hemen altı ve üstü gürültü nedeniyle bazen yayınlanır, bazen gizlenir
+        let below = published(4, &mut rng);
+        let above = published(6, &mut rng);
+        assert!(0 < below && below < 200);
+        assert!(0 < above && above < 200);
+        assert!(below < above);
+
+        // Gürültü yoksa eşik tam sayıma uygulanır
+        assert!(anonymize_cells(exact(4), 5, SuppressionMode::Suppress, None, &mut rng).0.is_empty());
+    }
+
+    #[test]
+    fn test_top_regions_hide_single_user_cells() {
+        let crowded = cell(41.0082, 28.9784);
+        let lonely = cell(39.9334, 32.8597);
+        let exact = || {
+            [(crowded, 7), (lonely, 1)]
+                .into_iter()
+                .map(|(index, count)| H3HeatmapCell {
+                    h3_index: index.to_string(),
+                    count,
+                    value: count as f64,
+                    user_count: Some(count),
+                    center_latitude: 39.9334,
+                    center_longitude: 32.8597,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for mode in [SuppressionMode::Suppress, SuppressionMode::Rollup] {
+            let regions = top_regions(exact(), 5, mode, 10);
+            assert_eq!(regions.len(), 1, "{:?}", mode);
+            assert_eq!(regions[0].h3_index, crowded.to_string());
+            assert_eq!(regions[0].count, 7);
+            // Merkez kullanıcı koordinatlarından değil H3 hücresinden gelir
+            assert_eq!((regions[0].center_latitude, regions[0].center_longitude), h3_to_coords(&regions[0].h3_index).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_privacy_budget() {
+        let budget = PrivacyBudget::new(1.0);
+        assert_eq!(budget.try_spend("a", 0.6), Ok(0.4));
+        assert!(budget.try_spend("a", 0.6).is_err());
+        assert_eq!(budget.try_spend("a", 0.4), Ok(0.0));
+        assert_eq!(budget.try_spend("b", 1.0), Ok(0.0));
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use actix_web::web;
+use chrono::Utc;
+
+use crate::database::Database;
+
+/// Temizleme işinin denetim kayıtlarındaki kimliği
+pub const PURGE_ACTOR: &str = "system:purge";
+
+/// Saklama süresi dolmuş soft-delete kayıtlarını periyodik olarak kalıcı siler
+///
+/// `interval` sıfırsa iş başlatılmaz.
+pub fn spawn_purge_job(db: web::Data<Database>, retention_days: u32, interval: Duration) {
+    if interval.is_zero() {
+        tracing::info!("Silinmiş kayıt temizleme işi kapalı (PURGE_INTERVAL_SECS=0)");
+        return;
+    }
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            let older_than = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+            match db.purge_deleted(older_than, PURGE_ACTOR).await {
+                Ok((0, 0)) => {}
+                Ok((users, ports)) => tracing::info!(
+                    retention_days,
+                    users,
+                    ports,
+                    "Saklama süresi dolmuş silinmiş kayıtlar temizlendi"
+                ),
+                Err(e) => tracing::error!(error = %e, "Silinmiş kayıt temizleme hatası"),
+            }
+        }
+    });
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage, HttpResponse, ResponseError};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::auth::{caller_key, Principal};
+use crate::config::AppConfig;
+#[cfg(test)]
+use crate::config::RateLimitConfig;
+use crate::error::AppError;
+
+/// Sınırlamanın uygulandığı route'ların ön eki; sağlık, metrik ve dokümantasyon route'ları sınırlanmaz
+const LIMITED_PREFIX: &str = "/api/";
+
+/// Kendi dakikalık sınırı olan maliyetli route'lar
+const HEATMAP_ROUTE: &str = "/api/v1/heatmap";
+const NEAREST_PORT_ROUTE: &str = "/api/v1/ports/nearest";
+
+/// Başarısız kimlik doğrulamaların sayıldığı bucket (route şablonlarıyla çakışmaz)
+const AUTH_FAILURE_BUCKET: &str = "auth-failures";
+
+/// Token bucket penceresi (saniye); bucket bu sürede tamamen dolar
+const WINDOW_SECS: u64 = 60;
+
+/// Günlük kota penceresi (saniye)
+const DAY_SECS: u64 = 86_400;
+
+/// Kullanılmayan bucket ve kotaların temizlenme aralığı
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Yanıtlarda bildirilen limit başlıkları (IETF RateLimit başlıkları taslağı)
+pub const RATE_LIMIT_LIMIT: &str = "ratelimit-limit";
+pub const RATE_LIMIT_REMAINING: &str = "ratelimit-remaining";
+pub const RATE_LIMIT_RESET: &str = "ratelimit-reset";
+pub const RATE_LIMIT_POLICY: &str = "ratelimit-policy";
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Son güncellemeden bu yana dolan token'ları ekler
+    fn refill(&mut self, capacity: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity / WINDOW_SECS as f64).min(capacity);
+        self.updated = now;
+    }
+
+    /// Bir token dolana kadar geçecek süre (saniye)
+    fn retry_after(&self, capacity: f64) -> u64 {
+        (((1.0 - self.tokens) * WINDOW_SECS as f64 / capacity).ceil() as u64).max(1)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// (çağıran, route şablonu) -> token bucket
+    buckets: HashMap<(String, String), Bucket>,
+    /// çağıran -> (UTC günü, o gün yapılan istek sayısı)
+    quotas: HashMap<String, (NaiveDate, u32)>,
+    last_sweep: Option<Instant>,
+}
+
+/// Bir pencerenin (dakikalık bucket veya günlük kota) durumu
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Window {
+    limit: u32,
+    remaining: u32,
+    reset_secs: u64,
+    window_secs: u64,
+}
+
+/// Bir isteğin sınır kontrolü sonucu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    minute: Option<Window>,
+    daily: Option<Window>,
+    /// İstek reddedildiyse aşılan sınırın hata kodu ve tekrar denenebileceği süre (saniye)
+    rejected: Option<(&'static str, u64)>,
+}
+
+impl Decision {
+    /// En çok tükenmiş pencereyi `RateLimit-*` başlıklarıyla, tüm pencereleri `RateLimit-Policy` ile bildirir
+    fn apply_headers(&self, headers: &mut HeaderMap) {
+        let windows: Vec<Window> = self.minute.into_iter().chain(self.daily).collect();
+        let Some(closest) = windows.iter().min_by_key(|w| (w.remaining, std::cmp::Reverse(w.reset_secs))) else {
+            return;
+        };
+
+        let policy = windows
+            .iter()
+            .map(|w| format!("{};w={}", w.limit, w.window_secs))
+            .collect::<Vec<_>>()
+            .join(", ");
+        for (name, value) in [
+            (RATE_LIMIT_LIMIT, closest.limit.to_string()),
+            (RATE_LIMIT_REMAINING, closest.remaining.to_string()),
+            (RATE_LIMIT_RESET, closest.reset_secs.to_string()),
+            (RATE_LIMIT_POLICY, policy),
+        ] {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+        }
+    }
+}
+
+/// Çağıran (API anahtarı, OIDC kimliği veya IP adresi) ve route başına token bucket ile dakikalık sınır,
+/// çağıran başına günlük kota uygulayan bellek içi sınırlayıcı
+///
+/// Durum süreç içinde tutulur; birden fazla kopya çalıştırılıyorsa her kopya kendi sınırlarını uygular.
+pub struct RateLimiter {
+    per_minute: u32,
+    heatmap_per_minute: u32,
+    nearest_port_per_minute: u32,
+    daily_quota: u32,
+    auth_failures_per_minute: u32,
+    trust_proxy_headers: bool,
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            per_minute: config.rate_limit.per_minute,
+            heatmap_per_minute: config.rate_limit.heatmap_per_minute,
+            nearest_port_per_minute: config.rate_limit.nearest_port_per_minute,
+            daily_quota: config.rate_limit.daily_quota,
+            auth_failures_per_minute: config.rate_limit.auth_failures_per_minute,
+            trust_proxy_headers: config.server.trust_proxy_headers,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Route şablonuna göre dakikalık sınır (0 ise sınırsız)
+    fn per_minute(&self, route: &str) -> u32 {
+        match route {
+            HEATMAP_ROUTE => self.heatmap_per_minute,
+            NEAREST_PORT_ROUTE => self.nearest_port_per_minute,
+            _ => self.per_minute,
+        }
+    }
+
+    /// İsteği sayar ve sınırların aşılıp aşılmadığını döndürür; reddedilen istekler kotadan düşülmez
+    pub fn check(&self, caller: &str, route: &str, now: Instant, today: DateTime<Utc>) -> Decision {
+        let per_minute = self.per_minute(route);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if state.last_sweep.is_none_or(|last| now.duration_since(last) >= SWEEP_INTERVAL) {
+            sweep(&mut state, now, today.date_naive());
+        }
+
+        let mut rejected = None;
+
+        let bucket = (per_minute > 0).then(|| {
+            let capacity = f64::from(per_minute);
+            let refill_per_sec = capacity / WINDOW_SECS as f64;
+            let bucket = state
+                .buckets
+                .entry((caller.to_string(), route.to_string()))
+                .or_insert(Bucket { tokens: capacity, updated: now });
+            bucket.refill(capacity, now);
+            if bucket.tokens < 1.0 {
+                rejected = Some(("rate_limited", bucket.retry_after(capacity)));
+            }
+            (capacity, refill_per_sec)
+        });
+
+        let seconds_to_midnight = {
+            let midnight = (today.date_naive() + chrono::Days::new(1)).and_time(chrono::NaiveTime::MIN).and_utc();
+            (midnight - today).num_seconds().max(1) as u64
+        };
+        if self.daily_quota > 0 && rejected.is_none() {
+            let (day, used) = state.quotas.entry(caller.to_string()).or_insert((today.date_naive(), 0));
+            if *day != today.date_naive() {
+                *day = today.date_naive();
+                *used = 0;
+            }
+            if *used >= self.daily_quota {
+                rejected = Some(("daily_quota_exceeded", seconds_to_midnight));
+            }
+        }
+
+        // İstek kabul edildiyse hem bucket'tan hem kotadan düş
+        if rejected.is_none() {
+            if let Some(bucket) = state.buckets.get_mut(&(caller.to_string(), route.to_string())) {
+                bucket.tokens -= 1.0;
+            }
+            if let Some((_, used)) = state.quotas.get_mut(caller) {
+                *used += 1;
+            }
+        }
+
+        let minute = bucket.and_then(|(capacity, refill_per_sec)| {
+            let bucket = state.buckets.get(&(caller.to_string(), route.to_string()))?;
+            Some(Window {
+                limit: per_minute,
+                remaining: bucket.tokens.max(0.0).floor() as u32,
+                reset_secs: ((capacity - bucket.tokens) / refill_per_sec).ceil().max(0.0) as u64,
+                window_secs: WINDOW_SECS,
+            })
+        });
+        let daily = (self.daily_quota > 0).then(|| {
+            let used = state.quotas.get(caller).map_or(0, |(_, used)| *used);
+            Window {
+                limit: self.daily_quota,
+                remaining: self.daily_quota.saturating_sub(used),
+                reset_secs: seconds_to_midnight,
+                window_secs: DAY_SECS,
+            }
+        });
+
+        Decision { minute, daily, rejected }
+    }
+
+    /// IP adresinin başarısız kimlik doğrulama bucket'ı boşsa tekrar denenebileceği süre (saniye)
+    fn auth_failure_retry_after(&self, ip: &str, now: Instant) -> Option<u64> {
+        if self.auth_failures_per_minute == 0 {
+            return None;
+        }
+        let capacity = f64::from(self.auth_failures_per_minute);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = state.buckets.get_mut(&(ip.to_string(), AUTH_FAILURE_BUCKET.to_string()))?;
+        bucket.refill(capacity, now);
+        (bucket.tokens < 1.0).then(|| bucket.retry_after(capacity))
+    }
+
+    /// Başarısız kimlik doğrulamayı IP adresinin bucket'ından düşer
+    fn record_auth_failure(&self, ip: &str, now: Instant) {
+        if self.auth_failures_per_minute == 0 {
+            return;
+        }
+        let capacity = f64::from(self.auth_failures_per_minute);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = state
+            .buckets
+            .entry((ip.to_string(), AUTH_FAILURE_BUCKET.to_string()))
+            .or_insert(Bucket { tokens: capacity, updated: now });
+        bucket.refill(capacity, now);
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+}
+
+/// Kimlik bilgisi taşıyan isteğin IP adresi başarısız deneme sınırını aştıysa 429 yanıtı
+///
+/// `authenticate` kimlik bilgisini denetlemeden önce çağırır; böylece anahtar tahmin denemeleri veritabanı
+/// veya JWKS sorgusuna yol açmadan reddedilir.
+pub fn reject_auth_attempt(req: &ServiceRequest) -> Option<HttpResponse> {
+    let limiter = req.app_data::<web::Data<RateLimiter>>()?;
+    let ip = caller_key(None, &req.connection_info(), limiter.trust_proxy_headers);
+    let retry_after = limiter.auth_failure_retry_after(&ip, Instant::now())?;
+
+    tracing::warn!(caller = %ip, "Başarısız kimlik doğrulama sınırı aşıldı");
+    let mut response = AppError::TooManyRequests {
+        code: "auth_rate_limited",
+        detail: format!("Çok fazla başarısız kimlik doğrulama; {} saniye sonra tekrar deneyin", retry_after),
+    }
+    .error_response();
+    response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+    Some(response)
+}
+
+/// Başarısız kimlik doğrulamayı isteğin IP adresine yazar
+pub fn record_auth_failure(req: &ServiceRequest) {
+    if let Some(limiter) = req.app_data::<web::Data<RateLimiter>>() {
+        let ip = caller_key(None, &req.connection_info(), limiter.trust_proxy_headers);
+        limiter.record_auth_failure(&ip, Instant::now());
+    }
+}
+
+/// Tamamen dolmuş bucket'ları ve önceki günlere ait kotaları siler
+fn sweep(state: &mut State, now: Instant, today: NaiveDate) {
+    state
+        .buckets
+        .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < Duration::from_secs(WINDOW_SECS));
+    state.quotas.retain(|_, (day, _)| *day == today);
+    state.last_sweep = Some(now);
+}
+
+/// `/api/` altındaki route'lara istek sınırı uygulayan middleware
+///
+/// `authenticate` middleware'inin içinde çalışmalıdır; kimliği doğrulanmış çağıranlar aktör kimliğiyle,
+/// anonim çağıranlar IP adresiyle sayılır. Başarısız kimlik doğrulamalar bu middleware'e ulaşmadığı için
+/// `authenticate` tarafından `reject_auth_attempt` ve `record_auth_failure` ile ayrıca sınırlanır. Her yanıta `RateLimit-*` başlıkları eklenir; sınır aşıldığında
+/// `Retry-After` başlığıyla 429 döner.
+pub async fn enforce(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+    let route = req.match_pattern().filter(|route| route.starts_with(LIMITED_PREFIX));
+    let (Some(limiter), Some(route)) = (limiter, route) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    // connection_info() extension'ları ödünç aldığından Principal önce kopyalanır
+    let principal = req.extensions().get::<Principal>().cloned();
+    let caller = caller_key(principal.as_ref(), &req.connection_info(), limiter.trust_proxy_headers);
+    let decision = limiter.check(&caller, &route, Instant::now(), Utc::now());
+
+    if let Some((code, retry_after)) = decision.rejected {
+        tracing::warn!(%caller, %route, code, "İstek sınırı aşıldı");
+        let detail = match code {
+            "daily_quota_exceeded" => format!("Günlük istek kotası doldu; {} saniye sonra yenilenir", retry_after),
+            _ => format!("Çok fazla istek; {} saniye sonra tekrar deneyin", retry_after),
+        };
+        let mut response = AppError::TooManyRequests { code, detail }.error_response();
+        decision.apply_headers(response.headers_mut());
+        response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    let mut response = next.call(req).await?;
+    decision.apply_headers(response.headers_mut());
+    Ok(response.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_minute: u32, heatmap_per_minute: u32, daily_quota: u32) -> RateLimiter {
+        RateLimiter::new(&AppConfig {
+            rate_limit: RateLimitConfig {
+                per_minute,
+                heatmap_per_minute,
+                daily_quota,
+                ..RateLimitConfig::default()
+            },
+            ..AppConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_token_bucket_per_route() {
+        let limiter = limiter(100, 2, 0);
+        let start = Instant::now();
+        let today = Utc::now();
+
+        assert_eq!(limiter.check("ip:1", HEATMAP_ROUTE, start, today).rejected, None);
+        assert_eq!(limiter.check("ip:1", HEATMAP_ROUTE, start, today).rejected, None);
+        // Heatmap bucket'ı boşaldı; diğer route'lar ve diğer çağıranlar etkilenmez
+        assert_eq!(limiter.check("ip:1", HEATMAP_ROUTE, start, today).rejected, Some(("rate_limited", 30)));
+        assert_eq!(limiter.check("ip:1", "/api/v1/users", start, today).rejected, None);
+        assert_eq!(limiter.check("ip:2", HEATMAP_ROUTE, start, today).rejected, None);
+
+        // Dakikada 2 token: 30 saniyede bir token dolar
+        let later = start + Duration::from_secs(30);
+        let decision = limiter.check("ip:1", HEATMAP_ROUTE, later, today);
+        assert_eq!(decision.rejected, None);
+        let minute = decision.minute.unwrap();
+        assert_eq!((minute.limit, minute.remaining, minute.reset_secs), (2, 0, 60));
+    }
+
+    #[test]
+    fn test_daily_quota_resets_at_midnight() {
+        let limiter = limiter(0, 0, 2);
+        let now = Instant::now();
+        let today = "2024-05-01T23:59:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(limiter.check("api_key:a", "/api/v1/users", now, today).rejected, None);
+        assert_eq!(limiter.check("api_key:a", "/api/v1/ports", now, today).rejected, None);
+        let decision = limiter.check("api_key:a", "/api/v1/users", now, today);
+        assert_eq!(decision.rejected, Some(("daily_quota_exceeded", 60)));
+        assert_eq!(decision.daily.unwrap().remaining, 0);
+
+        let tomorrow = today + chrono::Duration::minutes(2);
+        assert_eq!(limiter.check("api_key:a", "/api/v1/users", now, tomorrow).rejected, None);
+    }
+
+    #[actix_web::test]
+    async fn test_repeated_bad_keys_are_rate_limited() {
+        use actix_web::middleware::from_fn;
+        use actix_web::{test, App, HttpResponse};
+
+        let limiter = RateLimiter::new(&AppConfig {
+            rate_limit: RateLimitConfig { auth_failures_per_minute: 3, ..RateLimitConfig::default() },
+            ..AppConfig::default()
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(limiter))
+                .wrap(from_fn(enforce))
+                .wrap(from_fn(crate::auth::authenticate))
+                .route("/api/v1/users", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let request = |key: &str| {
+            test::TestRequest::get()
+                .uri("/api/v1/users")
+                .insert_header(("X-API-Key", key.to_string()))
+                .peer_addr("10.0.0.1:4000".parse().unwrap())
+                .to_request()
+        };
+
+        for _ in 0..3 {
+            assert_eq!(test::call_service(&app, request("yanlis")).await.status(), 401);
+        }
+        let response = test::call_service(&app, request("yanlis")).await;
+        assert_eq!(response.status(), 429);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+
+        // Kimlik bilgisi taşımayan istekler etkilenmez
+        let anonymous = test::TestRequest::get()
+            .uri("/api/v1/users")
+            .peer_addr("10.0.0.1:4000".parse().unwrap())
+            .to_request();
+        assert_eq!(test::call_service(&app, anonymous).await.status(), 200);
+    }
+
+    #[test]
+    fn test_headers_report_closest_window() {
+        let limiter = limiter(10, 10, 1000);
+        let decision = limiter.check("ip:1", "/api/v1/users", Instant::now(), Utc::now());
+        let mut headers = HeaderMap::new();
+        decision.apply_headers(&mut headers);
+
+        assert_eq!(headers.get(RATE_LIMIT_LIMIT).unwrap(), "10");
+        assert_eq!(headers.get(RATE_LIMIT_REMAINING).unwrap(), "9");
+        assert_eq!(headers.get(RATE_LIMIT_RESET).unwrap(), "6");
+        assert_eq!(headers.get(RATE_LIMIT_POLICY).unwrap(), "10;w=60, 1000;w=86400");
+    }
+}
@@ -1,15 +1,21 @@
 use actix_web::{web, HttpResponse};
+use crate::auth::{RequireScope, Scope};
 use crate::handlers::{
-    index, health_check, create_user, get_user, get_all_users, update_user, delete_user, get_stats,
-    get_all_ports, create_port, find_nearest_port, get_ports_by_country, get_ports_by_type, get_user_heatmap
+    index, liveness, readiness, metrics, create_user, get_user, get_all_users, update_user, delete_user, restore_user, import_users, export_users, get_stats,
+    get_all_ports, get_port, create_port, delete_port, restore_port, find_nearest_port, get_ports_by_country, get_ports_by_type, get_port_catchments, get_coverage_gaps, get_user_heatmap, get_heatmap_comparison,
+    get_all_api_keys, create_api_key, revoke_api_key, get_audit_log,
+    get_user_subject_access, erase_user, start_h3_audit, get_h3_audit
 };
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg
         // Ana routes
         .route("/", web::get().to(index))
-        .route("/health", web::get().to(health_check))
-        .route("/swagger-ui", web::get().to(|| async { 
+        .route("/health", web::get().to(liveness))
+        .route("/health/live", web::get().to(liveness))
+        .route("/health/ready", web::get().to(readiness))
+        .route("/metrics", web::get().to(metrics))
+        .route("/swagger-ui", web::get().to(|| async {
             actix_web::HttpResponse::Found()
                 .append_header(("Location", "/swagger-ui/"))
                 .finish()
@@ -21,27 +27,50 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 .content_type("text/html; charset=utf-8")
                 .body(html)
         }))
-        
-        // API v1 routes
+
+        // API v1 routes (her route kendi kapsamını ister)
         .service(
             web::scope("/api/v1")
-                .route("/stats", web::get().to(get_stats))
+                .route("/stats", web::get().to(get_stats).wrap(RequireScope::new(Scope::StatsRead)))
                 .route("/heatmap", web::get().to(get_user_heatmap))
+                .route("/heatmap/compare", web::get().to(get_heatmap_comparison))
                 .service(
                     web::scope("/users")
-                        .route("", web::get().to(get_all_users))
-                        .route("", web::post().to(create_user))
-                        .route("/{id}", web::get().to(get_user))
-                        .route("/{id}", web::put().to(update_user))
-                        .route("/{id}", web::delete().to(delete_user))
+                        .route("", web::get().to(get_all_users).wrap(RequireScope::new(Scope::UsersRead)))
+                        .route("", web::post().to(create_user).wrap(RequireScope::new(Scope::UsersWrite)))
+                        .route("/import", web::post().to(import_users).wrap(RequireScope::new(Scope::UsersWrite)))
+                        .route("/export", web::get().to(export_users).wrap(RequireScope::new(Scope::UsersRead)))
+                        .route("/{id}", web::get().to(get_user).wrap(RequireScope::new(Scope::UsersRead)))
+                        .route("/{id}", web::put().to(update_user).wrap(RequireScope::new(Scope::UsersWrite)))
+                        .route("/{id}", web::delete().to(delete_user).wrap(RequireScope::new(Scope::UsersWrite)))
+                        .route("/{id}/restore", web::post().to(restore_user).wrap(RequireScope::new(Scope::UsersWrite)))
                 )
                 .service(
                     web::scope("/ports")
-                        .route("", web::get().to(get_all_ports))
-                        .route("", web::post().to(create_port))
-                        .route("/nearest", web::post().to(find_nearest_port))
-                        .route("/country/{country}", web::get().to(get_ports_by_country))
-                        .route("/type/{port_type}", web::get().to(get_ports_by_type))
+                        .route("", web::get().to(get_all_ports).wrap(RequireScope::new(Scope::PortsRead)))
+                        .route("", web::post().to(create_port).wrap(RequireScope::new(Scope::PortsWrite)))
+                        .route("/nearest", web::post().to(find_nearest_port).wrap(RequireScope::new(Scope::PortsRead)))
+                        .route("/country/{country}", web::get().to(get_ports_by_country).wrap(RequireScope::new(Scope::PortsRead)))
+                        .route("/type/{port_type}", web::get().to(get_ports_by_type).wrap(RequireScope::new(Scope::PortsRead)))
+                        .route("/catchments", web::get().to(get_port_catchments).wrap(RequireScope::new(Scope::StatsRead)))
+                        .route("/coverage-gaps", web::get().to(get_coverage_gaps).wrap(RequireScope::new(Scope::StatsRead)))
+                        .route("/{id}", web::get().to(get_port).wrap(RequireScope::new(Scope::PortsRead)))
+                        .route("/{id}", web::delete().to(delete_port).wrap(RequireScope::new(Scope::PortsWrite)))
+                        .route("/{id}/restore", web::post().to(restore_port).wrap(RequireScope::new(Scope::PortsWrite)))
+                )
+                .service(
+                    web::scope("/admin")
+                        .service(
+                            web::scope("/api-keys")
+                                .route("", web::get().to(get_all_api_keys).wrap(RequireScope::new(Scope::Admin)))
+                                .route("", web::post().to(create_api_key).wrap(RequireScope::new(Scope::Admin)))
+                                .route("/{id}", web::delete().to(revoke_api_key).wrap(RequireScope::new(Scope::Admin)))
+                        )
+                        .route("/audit-log", web::get().to(get_audit_log).wrap(RequireScope::new(Scope::Admin)))
+                        .route("/users/{id}/subject-access", web::get().to(get_user_subject_access).wrap(RequireScope::new(Scope::Admin)))
+                        .route("/users/{id}/erasure", web::post().to(erase_user).wrap(RequireScope::new(Scope::Admin)))
+                        .route("/h3-audit", web::post().to(start_h3_audit).wrap(RequireScope::new(Scope::Admin)))
+                        .route("/h3-audit", web::get().to(get_h3_audit).wrap(RequireScope::new(Scope::Admin)))
                 )
         );
 }
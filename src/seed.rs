@@ -0,0 +1,268 @@
+use serde::Deserialize;
+
+use crate::database::Database;
+use crate::models::{CreatePort, CreateUser, LocationPrecision, Port, User};
+use crate::validation::Validate;
+
+/// Örnek verileri yükleyen komutun denetim kayıtlarındaki kimliği
+pub const SEED_ACTOR: &str = "system:seed";
+
+/// Uygulamaya gömülü fixture setleri (`fixtures/` dizini)
+const FIXTURE_SOURCES: &[(&str, &str)] = &[
+    ("fixtures/users.toml", include_str!("../fixtures/users.toml")),
+    ("fixtures/turkish-ports.toml", include_str!("../fixtures/turkish-ports.toml")),
+    ("fixtures/world-ports.toml", include_str!("../fixtures/world-ports.toml")),
+    ("fixtures/north-sea-baltic-ferries.toml", include_str!("../fixtures/north-sea-baltic-ferries.toml")),
+];
+
+/// Sürümlü örnek veri seti
+///
+/// Kayıtlar uygulamanın kendi yolundan eklenir: email'ler şifrelenir, H3 index'leri yükleme anındaki
+/// çözünürlükle hesaplanır ve denetim kaydı yazılır. Sete kayıt eklendiğinde sürüm artırılmalıdır; yüklenmiş
+/// sürümden yeni olmayan setler tekrar yüklenmez.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureSet {
+    pub name: String,
+    pub version: i32,
+    pub description: String,
+    #[serde(default)]
+    pub users: Vec<CreateUser>,
+    #[serde(default)]
+    pub ports: Vec<CreatePort>,
+}
+
+/// Bir setin yükleme sonucu
+#[derive(Debug, Default)]
+pub struct SeedReport {
+    pub users_created: usize,
+    pub users_skipped: usize,
+    pub ports_created: usize,
+    pub ports_skipped: usize,
+}
+
+/// Gömülü fixture setlerini ayrıştırır ve doğrular
+pub fn fixture_sets() -> Result<Vec<FixtureSet>, String> {
+    FIXTURE_SOURCES
+        .iter()
+        .map(|(path, source)| {
+            let set: FixtureSet = toml::from_str(source).map_err(|e| format!("{}: {}", path, e.message()))?;
+            for user in &set.users {
+                user.validate().map_err(|e| format!("{}: {}: {}", path, user.email, e))?;
+            }
+            for port in &set.ports {
+                port.validate().map_err(|e| format!("{}: {}: {}", path, port.code, e))?;
+            }
+            Ok(set)
+        })
+        .collect()
+}
+
+/// Seti yükler; aynı email'e veya koda sahip kayıtlar atlandığı için tekrar çalıştırılabilir
+pub async fn load(db: &Database, set: &FixtureSet) -> Result<SeedReport, sqlx::Error> {
+    let mut report = SeedReport::default();
+
+    for user in &set.users {
+        if db.get_user_by_email(&user.email).await?.is_some() {
+            report.users_skipped += 1;
+            continue;
+        }
+        match db.create_user(user.clone(), SEED_ACTOR).await {
+            Ok(_) => report.users_created += 1,
+            // Silinmiş (henüz temizlenmemiş) bir kayıt aynı email'i tutuyor olabilir
+            Err(e) if is_unique_violation(&e) => report.users_skipped += 1,
+            Err(e) => return Err(e),
+        }
+    }
+
+    for port in &set.ports {
+        if db.get_port_by_code(&port.code).await?.is_some() {
+            report.ports_skipped += 1;
+            continue;
+        }
+        match db.create_port(port.clone(), SEED_ACTOR).await {
+            Ok(_) => report.ports_created += 1,
+            Err(e) if is_unique_violation(&e) => report.ports_skipped += 1,
+            Err(e) => return Err(e),
+        }
+    }
+
+    db.record_fixture_set(&set.name, set.version).await?;
+    Ok(report)
+}
+
+/// 001-003 migrasyonlarının eskiden eklediği örnek kullanıcı
+struct LegacyUser {
+    name: &'static str,
+    email: &'static str,
+    latitude: f64,
+    longitude: f64,
+    h3_index: &'static str,
+}
+
+/// 003 migrasyonunun eskiden eklediği örnek liman
+struct LegacyPort {
+    name: &'static str,
+    code: &'static str,
+    country: &'static str,
+    city: &'static str,
+    latitude: f64,
+    longitude: f64,
+    h3_index: &'static str,
+    port_type: &'static str,
+    capacity: i32,
+}
+
+const LEGACY_USERS: &[LegacyUser] = &[
+    LegacyUser { name: "Ahmet Yılmaz", email: "ahmet@example.com", latitude: 41.0082, longitude: 28.9784, h3_index: "891fb466257ffff" },
+    LegacyUser { name: "Mehmet Demir", email: "mehmet@example.com", latitude: 39.9334, longitude: 32.8597, h3_index: "891ea6992b7ffff" },
+    LegacyUser { name: "Ayşe Kaya", email: "ayse@example.com", latitude: 38.4237, longitude: 27.1428, h3_index: "891f1d6b89fffff" },
+];
+
+const LEGACY_PORTS: &[LegacyPort] = &[
+    LegacyPort { name: "Ambarlı Limanı", code: "TRAMB", country: "Turkey", city: "Istanbul", latitude: 40.9770, longitude: 28.6850, h3_index: "891ec90b52fffff", port_type: "container", capacity: 2500000 },
+    LegacyPort { name: "Haydarpaşa Limanı", code: "TRHAY", country: "Turkey", city: "Istanbul", latitude: 40.9990, longitude: 29.0180, h3_index: "891ec91434fffff", port_type: "cargo", capacity: 500000 },
+    LegacyPort { name: "İzmir Alsancak Limanı", code: "TRIZM", country: "Turkey", city: "Izmir", latitude: 38.4480, longitude: 27.1350, h3_index: "891f1d6baabffff", port_type: "container", capacity: 1200000 },
+    LegacyPort { name: "Mersin Limanı", code: "TRMER", country: "Turkey", city: "Mersin", latitude: 36.8000, longitude: 34.6333, h3_index: "891f574062fffff", port_type: "container", capacity: 1800000 },
+    LegacyPort { name: "Samsun Limanı", code: "TRSAM", country: "Turkey", city: "Samsun", latitude: 41.2928, longitude: 36.3313, h3_index: "891ea42692fffff", port_type: "cargo", capacity: 400000 },
+    LegacyPort { name: "Trabzon Limanı", code: "TRTRB", country: "Turkey", city: "Trabzon", latitude: 40.9980, longitude: 39.7769, h3_index: "891ea551b8fffff", port_type: "cargo", capacity: 300000 },
+    LegacyPort { name: "Iskenderun Limanı", code: "TRISK", country: "Turkey", city: "Iskenderun", latitude: 36.5833, longitude: 36.1667, h3_index: "891f56c5c6fffff", port_type: "container", capacity: 1000000 },
+    LegacyPort { name: "Bandırma Limanı", code: "TRBAN", country: "Turkey", city: "Bandirma", latitude: 40.3500, longitude: 27.9833, h3_index: "891ec5b152fffff", port_type: "cargo", capacity: 200000 },
+    LegacyPort { name: "Çanakkale Limanı", code: "TRCAN", country: "Turkey", city: "Canakkale", latitude: 40.1553, longitude: 26.4142, h3_index: "891ec4b28afffff", port_type: "cargo", capacity: 150000 },
+    LegacyPort { name: "Antalya Limanı", code: "TRANT", country: "Turkey", city: "Antalya", latitude: 36.8841, longitude: 30.7056, h3_index: "891f50d492fffff", port_type: "cruise", capacity: 100000 },
+    LegacyPort { name: "Port of Shanghai", code: "CNSHA", country: "China", city: "Shanghai", latitude: 31.2304, longitude: 121.4737, h3_index: "8922816a553ffff", port_type: "container", capacity: 47000000 },
+    LegacyPort { name: "Port of Singapore", code: "SGSIN", country: "Singapore", city: "Singapore", latitude: 1.2966, longitude: 103.8518, h3_index: "8922a4c8443ffff", port_type: "container", capacity: 37200000 },
+    LegacyPort { name: "Port of Rotterdam", code: "NLRTM", country: "Netherlands", city: "Rotterdam", latitude: 51.9244, longitude: 4.4777, h3_index: "891fb1b1517ffff", port_type: "container", capacity: 14800000 },
+    LegacyPort { name: "Port of Hamburg", code: "DEHAM", country: "Germany", city: "Hamburg", latitude: 53.5511, longitude: 9.9937, h3_index: "891fb4b0527ffff", port_type: "container", capacity: 8800000 },
+    LegacyPort { name: "Port of Los Angeles", code: "USLAX", country: "USA", city: "Los Angeles", latitude: 33.7362, longitude: -118.2647, h3_index: "892411b5b07ffff", port_type: "container", capacity: 9300000 },
+    LegacyPort { name: "Port of Piraeus", code: "GRPIR", country: "Greece", city: "Piraeus", latitude: 37.9385, longitude: 23.6442, h3_index: "891f59c940bffff", port_type: "container", capacity: 5400000 },
+];
+
+impl LegacyUser {
+    // Kayıt migrasyonun eklediği hâliyle mi duruyor (hiç düzenlenmemiş)
+    fn matches(&self, user: &User) -> bool {
+        user.name == self.name
+            && user.email == self.email
+            && user.latitude == Some(self.latitude)
+            && user.longitude == Some(self.longitude)
+            && user.h3_index.as_deref() == Some(self.h3_index)
+            && user.location_precision == LocationPrecision::Exact
+            && user.updated_at == user.created_at
+            && user.deleted_at.is_none()
+            && user.erased_at.is_none()
+    }
+}
+
+impl LegacyPort {
+    // Kayıt migrasyonun eklediği hâliyle mi duruyor (hiç düzenlenmemiş)
+    fn matches(&self, port: &Port) -> bool {
+        port.name == self.name
+            && port.code == self.code
+            && port.country == self.country
+            && port.city == self.city
+            && port.latitude == self.latitude
+            && port.longitude == self.longitude
+            && port.h3_index == self.h3_index
+            && port.port_type == self.port_type
+            && port.capacity == Some(self.capacity)
+            && port.updated_at == port.created_at
+            && port.deleted_at.is_none()
+    }
+}
+
+/// Eski migrasyonların eklediği örnek kayıtları kaldırma sonucu
+#[derive(Debug, Default)]
+pub struct LegacyCleanupReport {
+    pub users_deleted: usize,
+    /// Email'i eşleşen ama sonradan değiştirilmiş (veya başka biri tarafından oluşturulmuş) kullanıcılar
+    pub users_kept: usize,
+    pub ports_deleted: usize,
+    /// Kodu eşleşen ama sonradan değiştirilmiş (veya gerçek kayıt olan) limanlar
+    pub ports_kept: usize,
+}
+
+/// 001-003 migrasyonlarının eskiden eklediği örnek kayıtları siler
+///
+/// Yalnızca her alanı migrasyondaki değerle aynı olan ve hiç güncellenmemiş kayıtlar silinir; silme API'deki gibi
+/// soft delete'tir ve denetim kaydına yazılır, kalıcı silme saklama süresi dolunca temizleme işiyle olur.
+pub async fn remove_legacy_samples(db: &Database) -> Result<LegacyCleanupReport, sqlx::Error> {
+    let mut report = LegacyCleanupReport::default();
+
+    for legacy in LEGACY_USERS {
+        match db.get_user_by_email(legacy.email).await? {
+            Some(user) if legacy.matches(&user) => {
+                report.users_deleted += usize::from(db.delete_user(user.id, SEED_ACTOR).await?);
+            }
+            Some(_) => report.users_kept += 1,
+            None => {}
+        }
+    }
+
+    for legacy in LEGACY_PORTS {
+        match db.get_port_by_code(legacy.code).await? {
+            Some(port) if legacy.matches(&port) => {
+                report.ports_deleted += usize::from(db.delete_port(port.id, SEED_ACTOR).await?);
+            }
+            Some(_) => report.ports_kept += 1,
+            None => {}
+        }
+    }
+
+    Ok(report)
+}
+
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(e) if e.code().as_deref() == Some("23505"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_embedded_fixtures_are_valid_and_unique() {
+        let sets = fixture_sets().unwrap();
+
+        let mut names = HashSet::new();
+        let mut codes = HashSet::new();
+        for set in &sets {
+            assert!(names.insert(set.name.as_str()), "tekrarlanan set: {}", set.name);
+            for port in &set.ports {
+                assert!(codes.insert(port.code.as_str()), "tekrarlanan liman kodu: {}", port.code);
+            }
+        }
+
+        let ferries = sets.iter().find(|set| set.name == "north-sea-baltic-ferries").unwrap();
+        assert!(ferries.ports.iter().all(|port| port.port_type == "ferry"));
+    }
+
+    #[test]
+    fn test_only_untouched_legacy_ports_match() {
+        let legacy = &LEGACY_PORTS[0];
+        let created_at = chrono::Utc::now();
+        let port = Port {
+            id: uuid::Uuid::new_v4(),
+            name: legacy.name.to_string(),
+            code: legacy.code.to_string(),
+            country: legacy.country.to_string(),
+            city: legacy.city.to_string(),
+            latitude: legacy.latitude,
+            longitude: legacy.longitude,
+            h3_index: legacy.h3_index.to_string(),
+            port_type: legacy.port_type.to_string(),
+            capacity: Some(legacy.capacity),
+            created_at,
+            updated_at: created_at,
+            deleted_at: None,
+        };
+        assert!(legacy.matches(&port));
+
+        // Sonradan güncellenmiş veya API'den aynı değerlerle oluşturulmuş (farklı çözünürlükte index) kayıtlar korunur
+        let edited = Port { updated_at: created_at + chrono::Duration::seconds(1), ..port.clone() };
+        assert!(!legacy.matches(&edited));
+        let recreated = Port { h3_index: crate::h3_utils::coords_to_h3(legacy.latitude, legacy.longitude, 7).unwrap(), ..port };
+        assert!(!legacy.matches(&recreated));
+    }
+}
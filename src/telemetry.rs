@@ -0,0 +1,172 @@
+use std::str::FromStr;
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::otlp::{self, TRACEPARENT_HEADER};
+
+/// İsteği uçtan uca izlemek için kullanılan başlık; gelen değer geçerliyse korunur, yoksa üretilir
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// İstemciden kabul edilecek en uzun istek kimliği
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Log çıktı biçimi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Satır başına bir JSON nesnesi (log toplayıcılar için)
+    Json,
+    /// İnsan tarafından okunabilir metin (yerel geliştirme için)
+    Text,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(LogFormat::Json),
+            "text" | "pretty" => Ok(LogFormat::Text),
+            other => Err(format!("Geçersiz log biçimi: {} (json veya text olmalı)", other)),
+        }
+    }
+}
+
+/// Global tracing subscriber'ını kurar; `log` crate'i üzerinden gelen kayıtlar da (actix, sqlx) aynı çıktıya yönlenir
+///
+/// `RUST_LOG` yalnızca log çıktısını filtreler; OTLP collector tanımlıysa INFO seviyesindeki tüm span'lar
+/// log seviyesinden bağımsız olarak dışa aktarılır.
+pub fn init(config: &AppConfig) -> Result<(), String> {
+    let telemetry = &config.telemetry;
+    let filter = EnvFilter::try_new(&telemetry.log_level)
+        .map_err(|e| format!("Geçersiz RUST_LOG değeri '{}': {}", telemetry.log_level, e))?;
+
+    let logs = match telemetry.log_format {
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .boxed(),
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+    };
+    let traces = telemetry.otlp_endpoint.as_deref().map(|endpoint| {
+        otlp::init(endpoint, &telemetry.service_name, telemetry.sampling_ratio).with_filter(LevelFilter::INFO)
+    });
+
+    tracing_subscriber::registry()
+        .with(logs.with_filter(filter))
+        .with(traces)
+        .try_init()
+        .map_err(|e| format!("Logger başlatılamadı: {}", e))?;
+
+    if let Some(endpoint) = &telemetry.otlp_endpoint {
+        tracing::info!(%endpoint, sampling_ratio = telemetry.sampling_ratio, "OTLP trace dışa aktarımı etkin");
+    }
+    Ok(())
+}
+
+/// İstemcinin gönderdiği kimliği yalnızca makul uzunlukta ve görünür ASCII karakterlerden oluşuyorsa kabul eder
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Her isteğe bir korelasyon kimliği atayan, isteği bu kimliği taşıyan bir span içinde çalıştıran ve
+/// kimliği `X-Request-Id` yanıt başlığında döndüren middleware. Veritabanı span'ları bu span'ın altında açılır;
+/// istekteki W3C `traceparent` başlığı span'a eklenir, böylece trace üst servisin trace'inin devamı olur.
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let traceparent = req
+        .headers()
+        .get(TRACEPARENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let span = tracing::info_span!(
+        "request",
+        otel.kind = "server",
+        otel.name = tracing::field::Empty,
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.path(),
+        http.route = tracing::field::Empty,
+        http.response.status_code = tracing::field::Empty,
+        traceparent = traceparent,
+    );
+    let started = Instant::now();
+
+    let mut result = next.call(req).instrument(span.clone()).await;
+
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let _entered = span.enter();
+    match &mut result {
+        Ok(res) => {
+            let status = res.status().as_u16();
+            if let Some(route) = res.request().match_pattern() {
+                span.record("otel.name", format!("{} {}", res.request().method(), route));
+                span.record("http.route", route);
+            }
+            span.record("http.response.status_code", status);
+            if res.status().is_server_error() {
+                tracing::error!(status, latency_ms, "İstek tamamlandı");
+            } else {
+                tracing::info!(status, latency_ms, "İstek tamamlandı");
+            }
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+        }
+        Err(e) => {
+            let status = e.as_response_error().status_code().as_u16();
+            span.record("http.response.status_code", status);
+            tracing::error!(status, latency_ms, error = %e, "İstek başarısız");
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_validation() {
+        assert!(is_valid_request_id("5f0c2b9e-7d1a-4c1e-9a57-0d7f3c2e1b4a"));
+        assert!(is_valid_request_id("lb-1234/abc"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("iki kelime"));
+        assert!(!is_valid_request_id("satır\nsonu"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[test]
+    fn test_log_format_parsing() {
+        assert_eq!("JSON".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use std::fmt;
+use std::ops::Deref;
+
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, ResponseError};
+use chrono::Utc;
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::models::{
+    CreateApiKey, CreatePort, CreateUser, EraseUserRequest, FindNearestPortRequest, H3AuditRequest, LocationErasure,
+    UpdatePort, UpdateUser, PORT_TYPES,
+};
+use crate::h3_audit::MAX_BATCH_SIZE;
+
+pub const MAX_NAME_LENGTH: usize = 255;
+pub const MAX_EMAIL_LENGTH: usize = 254;
+/// Silme talebinde konumun indirgenebileceği en yüksek H3 çözünürlüğü (~36 km² hücre)
+pub const MAX_ERASURE_RESOLUTION: u8 = 6;
+/// Silme talebinde varsayılan H3 çözünürlüğü (~250 km² hücre)
+pub const DEFAULT_ERASURE_RESOLUTION: u8 = 5;
+
+/// Tek bir alan için doğrulama hatası
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Hatalı alanın adı (örn: "latitude")
+    pub field: String,
+    /// Makine tarafından okunabilir hata kodu (örn: "out_of_range")
+    pub code: String,
+    /// İnsan tarafından okunabilir açıklama
+    pub message: String,
+}
+
+/// Bir istek modelindeki tüm doğrulama hataları
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Zorunlu, boş olmayan ve uzunluk sınırını aşmayan metin
+    pub fn text(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.trim().is_empty() {
+            self.add(field, "required", "Bu alan boş olamaz");
+        } else if value.chars().count() > max_length {
+            self.add(field, "too_long", format!("En fazla {} karakter olabilir", max_length));
+        }
+    }
+
+    pub fn email(&mut self, field: &str, value: &str) {
+        if value.len() > MAX_EMAIL_LENGTH {
+            self.add(field, "too_long", format!("En fazla {} karakter olabilir", MAX_EMAIL_LENGTH));
+        } else if !is_valid_email(value) {
+            self.add(field, "invalid_email", "Geçerli bir e-posta adresi olmalı");
+        }
+    }
+
+    pub fn range(&mut self, field: &str, value: f64, min: f64, max: f64) {
+        if !value.is_finite() || value < min || value > max {
+            self.add(field, "out_of_range", format!("{} ile {} arasında olmalı", min, max));
+        }
+    }
+
+    pub fn latitude(&mut self, field: &str, value: f64) {
+        self.range(field, value, -90.0, 90.0);
+    }
+
+    pub fn longitude(&mut self, field: &str, value: f64) {
+        self.range(field, value, -180.0, 180.0);
+    }
+
+    /// Enlem ve boylam ya birlikte verilmeli ya da hiç verilmemeli
+    pub fn location(&mut self, latitude: Option<f64>, longitude: Option<f64>) {
+        if let Some(lat) = latitude {
+            self.latitude("latitude", lat);
+        }
+        if let Some(lng) = longitude {
+            self.longitude("longitude", lng);
+        }
+        match (latitude, longitude) {
+            (Some(_), None) => self.add("longitude", "location_incomplete", "latitude ile birlikte longitude da verilmeli"),
+            (None, Some(_)) => self.add("latitude", "location_incomplete", "longitude ile birlikte latitude da verilmeli"),
+            _ => {}
+        }
+    }
+
+    pub fn port_code(&mut self, field: &str, value: &str) {
+        let valid = (2..=10).contains(&value.len())
+            && value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        if !valid {
+            self.add(field, "invalid_format", "2-10 karakterlik büyük harf/rakam olmalı (örn: TRIST)");
+        }
+    }
+
+    pub fn port_type(&mut self, field: &str, value: &str) {
+        if !PORT_TYPES.contains(&value) {
+            self.add(field, "invalid_choice", format!("Şunlardan biri olmalı: {}", PORT_TYPES.join(", ")));
+        }
+    }
+
+    pub fn capacity(&mut self, field: &str, value: Option<i32>) {
+        if value.is_some_and(|capacity| capacity < 0) {
+            self.add(field, "out_of_range", "Negatif olamaz");
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields: Vec<&str> = self.errors.iter().map(|e| e.field.as_str()).collect();
+        write!(f, "Geçersiz alanlar: {}", fields.join(", "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        AppError::Validation(self.errors.clone()).error_response()
+    }
+}
+
+/// Basit, bağımlılıksız e-posta kontrolü (yerel@alan.uzanti)
+pub fn is_valid_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !value.chars().any(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+}
+
+/// Doğrulanabilir istek modelleri
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+impl Validate for CreateUser {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.text("name", &self.name, MAX_NAME_LENGTH);
+        errors.email("email", &self.email);
+        errors.location(self.latitude, self.longitude);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateUser {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(name) = &self.name {
+            errors.text("name", name, MAX_NAME_LENGTH);
+        }
+        if let Some(email) = &self.email {
+            errors.email("email", email);
+        }
+        errors.location(self.latitude, self.longitude);
+        errors.into_result()
+    }
+}
+
+impl Validate for CreatePort {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.text("name", &self.name, MAX_NAME_LENGTH);
+        errors.port_code("code", &self.code);
+        errors.text("country", &self.country, MAX_NAME_LENGTH);
+        errors.text("city", &self.city, MAX_NAME_LENGTH);
+        errors.latitude("latitude", self.latitude);
+        errors.longitude("longitude", self.longitude);
+        errors.port_type("port_type", &self.port_type);
+        errors.capacity("capacity", self.capacity);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdatePort {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(name) = &self.name {
+            errors.text("name", name, MAX_NAME_LENGTH);
+        }
+        if let Some(code) = &self.code {
+            errors.port_code("code", code);
+        }
+        if let Some(country) = &self.country {
+            errors.text("country", country, MAX_NAME_LENGTH);
+        }
+        if let Some(city) = &self.city {
+            errors.text("city", city, MAX_NAME_LENGTH);
+        }
+        errors.location(self.latitude, self.longitude);
+        if let Some(port_type) = &self.port_type {
+            errors.port_type("port_type", port_type);
+        }
+        errors.capacity("capacity", self.capacity);
+        errors.into_result()
+    }
+}
+
+impl Validate for FindNearestPortRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.latitude("latitude", self.latitude);
+        errors.longitude("longitude", self.longitude);
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateApiKey {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.text("name", &self.name, MAX_NAME_LENGTH);
+        if self.scopes.is_empty() {
+            errors.add("scopes", "required", "En az bir kapsam verilmeli");
+        }
+        if self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            errors.add("expires_at", "out_of_range", "Gelecekte bir tarih olmalı");
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for EraseUserRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(resolution) = self.resolution {
+            if self.location == LocationErasure::Delete {
+                errors.add("resolution", "not_applicable", "Yalnızca location=coarsen ile kullanılabilir");
+            } else if resolution > MAX_ERASURE_RESOLUTION {
+                errors.add("resolution", "out_of_range", format!("0 ile {} arasında olmalı", MAX_ERASURE_RESOLUTION));
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for H3AuditRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.batch_size.is_some_and(|size| !(1..=MAX_BATCH_SIZE).contains(&size)) {
+            errors.add("batch_size", "out_of_range", format!("1 ile {} arasında olmalı", MAX_BATCH_SIZE));
+        }
+        errors.into_result()
+    }
+}
+
+/// JSON gövdesini çözümleyip doğrulayan extractor
+///
+/// Doğrulama başarısız olursa handler çağrılmadan alan bazında hatalarla 422 döner.
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for Valid<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+        Box::pin(async move {
+            let value = json.await?.into_inner();
+            value.validate()?;
+            Ok(Valid(value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(errors: ValidationErrors) -> Vec<(String, String)> {
+        errors.errors.into_iter().map(|e| (e.field, e.code)).collect()
+    }
+
+    #[test]
+    fn test_email_format() {
+        assert!(is_valid_email("ahmet@example.com"));
+        assert!(is_valid_email("a.b+c@sub.example.co"));
+        assert!(!is_valid_email("ahmet"));
+        assert!(!is_valid_email("ahmet@localhost"));
+        assert!(!is_valid_email("a@b@example.com"));
+        assert!(!is_valid_email("ahmet @example.com"));
+    }
+
+    #[test]
+    fn test_create_user_reports_every_invalid_field() {
+        let user = CreateUser {
+            name: " ".to_string(),
+            email: "not-an-email".to_string(),
+            latitude: Some(91.0),
+            longitude: None,
+            location_precision: None,
+        };
+        assert_eq!(
+            codes(user.validate().unwrap_err()),
+            vec![
+                ("name".to_string(), "required".to_string()),
+                ("email".to_string(), "invalid_email".to_string()),
+                ("latitude".to_string(), "out_of_range".to_string()),
+                ("longitude".to_string(), "location_incomplete".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_port_rules() {
+        let port = CreatePort {
+            name: "Ambarlı Limanı".to_string(),
+            code: "tramb".to_string(),
+            country: "Turkey".to_string(),
+            city: "Istanbul".to_string(),
+            latitude: 40.977,
+            longitude: 228.685,
+            port_type: "marina".to_string(),
+            capacity: Some(-1),
+        };
+        assert_eq!(
+            codes(port.validate().unwrap_err()),
+            vec![
+                ("code".to_string(), "invalid_format".to_string()),
+                ("longitude".to_string(), "out_of_range".to_string()),
+                ("port_type".to_string(), "invalid_choice".to_string()),
+                ("capacity".to_string(), "out_of_range".to_string()),
+            ]
+        );
+    }
+}